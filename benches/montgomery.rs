@@ -0,0 +1,118 @@
+//! `Field`, `Fp` と `MontFp` の速度比較.
+//!
+//! 剰余の計算だけを比べるため, 多項式の掛け算はどの型も同じ筆算で計る
+//! (`Manipulative` の掛け算は `Field` だけ数論変換を使うことがある).
+//!
+//! `cargo bench --bench montgomery` で実行する.
+use finite_field::prelude::*;
+use finite_field::traits::Ring;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const DEG: usize = 2000;
+/// スカラーの掛け算を計る列の長さ
+const SCALAR_LEN: usize = 1 << 16;
+/// 復号の計測に使う符号長
+const CODE_LEN: usize = 255;
+/// 復号の計測に使う設計距離. 誤りは訂正できる上限の (d - 1) / 2 個加える.
+const CODE_DIST: usize = 33;
+
+/// 1回空回ししてから, 合わせて200ms以上になるまで繰り返した平均
+fn measure<F: FnMut()>(mut f: F) -> Duration {
+    f();
+    let start = Instant::now();
+    let mut count = 0;
+    while count < 5 || start.elapsed() < Duration::from_millis(200) {
+        f();
+        count += 1;
+    }
+    start.elapsed() / count
+}
+fn coefficients(len: usize, seed: u64) -> Vec<i64> {
+    let mut x = seed;
+    (0..len)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            (x >> 2) as i64
+        })
+        .collect()
+}
+/// 筆算での多項式の掛け算
+fn schoolbook<T: Ring>(lhs: &[T], rhs: &[T]) -> Vec<T> {
+    let mut result = vec![lhs[0].zero_like(); lhs.len() + rhs.len() - 1];
+    for (i, l) in lhs.iter().enumerate() {
+        for (j, r) in rhs.iter().enumerate() {
+            result[i + j] += l.clone() * r.clone();
+        }
+    }
+    result
+}
+/// 各元の `exp` 乗 (繰り返し2乗法)
+fn pow_all<T: Ring>(xs: &[T], exp: u64) -> Vec<T> {
+    xs.iter()
+        .map(|x| {
+            let (mut acc, mut base, mut e) = (x.one_like(), x.clone(), exp);
+            while e > 0 {
+                if e & 1 == 1 {
+                    acc *= base.clone();
+                }
+                base *= base.clone();
+                e >>= 1;
+            }
+            acc
+        })
+        .collect()
+}
+macro_rules! bench {
+    ($name:expr, $p:expr, $new:expr) => {{
+        let new = $new;
+        let xs: Vec<_> = coefficients(SCALAR_LEN, 4).into_iter().map(new).collect();
+        let a: Vec<_> = coefficients(DEG, 1).into_iter().map(new).collect();
+        let b: Vec<_> = coefficients(DEG, 2).into_iter().map(new).collect();
+        // 符号語に訂正できる数の誤りを加えた受信語
+        let code = Code::new(CODE_LEN, CODE_DIST, new(3));
+        let msg: Vec<_> = coefficients(code.dimension(), 3)
+            .into_iter()
+            .map(new)
+            .collect();
+        let c = code.encrypt(&msg);
+        let mut y = c.factors_padded(CODE_LEN);
+        let errors = coefficients((CODE_DIST - 1) / 2, 5);
+        for (i, e) in errors.into_iter().enumerate() {
+            y[i * 15 + 2] += new(e % 1000 + 1);
+        }
+        assert_eq!(code.euclid_decrypt(&y).unwrap().codeword, c);
+        let scalar = measure(|| {
+            let mut acc = new(1);
+            for x in &xs {
+                acc *= *x;
+            }
+            black_box(acc);
+        });
+        let pow = measure(|| {
+            black_box(pow_all(&xs[..1024], $p - 2));
+        });
+        let mul = measure(|| {
+            black_box(schoolbook(&a, &b));
+        });
+        let decode = measure(|| {
+            let _ = black_box(code.euclid_decrypt(&y));
+        });
+        println!(
+            "{:<20} scalar: {:>10.3?}  pow: {:>10.3?}  mul: {:>10.3?}  decode: {:>10.3?}",
+            $name, scalar, pow, mul, decode
+        );
+    }};
+}
+fn main() {
+    const P30: u64 = 998_244_353;
+    const P62: u64 = 4_611_686_018_427_387_847;
+    bench!("Field (30 bit)", P30, |v| Field::new(v, P30));
+    bench!("Fp (30 bit)", P30, Fp::<P30>::new);
+    bench!("MontFp (30 bit)", P30, MontFp::<P30>::new);
+    bench!("Field (62 bit)", P62, |v| Field::new(v, P62));
+    bench!("Fp (62 bit)", P62, Fp::<P62>::new);
+    bench!("MontFp (62 bit)", P62, MontFp::<P62>::new);
+}
@@ -0,0 +1,178 @@
+use super::manipulative::Manipulative;
+use super::traits::FieldElement;
+
+/// Reed-Solomon符号の記述.
+///
+/// 符号長 `n`, 設計距離 `d`, 体の元 `a` と最初の零点の指数 `b` で
+/// C={f(a^i) (b<=i<=b+d-2) = 0} を定める. `b = 1` が狭義の符号.
+/// 符号化は `encrypt`, 復号は `euclid_decrypt` などのメソッドで行う.
+/// # Example
+/// ```
+/// # use finite_field::code::Code;
+/// # use finite_field::modulo::Field;
+/// // 零点が 2^0, 2^1, 2^2, 2^3 の GF(11) 上の (10, 6) 符号
+/// let code = Code::with_first_root(10, 5, Field::new(2, 11), 0);
+/// let msg: Vec<_> = [3, 1, 4, 1, 5, 9].iter().map(|v| Field::new(*v, 11)).collect();
+/// let c = code.encrypt(&msg);
+/// let mut y = c.factors_padded(10);
+/// y[2] += Field::new(1, 11);
+/// y[7] += Field::new(3, 11);
+/// let w = code.euclid_decrypt(&y).unwrap();
+/// assert_eq!(w.codeword, c);
+/// assert_eq!(w.error_values, [Field::new(1, 11), Field::new(3, 11)]);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Code<T> {
+    n: usize,
+    d: usize,
+    a: T,
+    b: usize,
+}
+impl<T: FieldElement> Code<T> {
+    /// 零点が a, a^2, ..., a^(d-1) の狭義の符号. aの位数の判定は行わない.
+    /// # Panics
+    /// `2 <= d <= n` でないとき, `a` が0のとき
+    pub fn new(n: usize, d: usize, a: T) -> Self {
+        Self::with_first_root(n, d, a, 1)
+    }
+    /// 零点が a^b, a^(b+1), ..., a^(b+d-2) の符号.
+    /// # Panics
+    /// `2 <= d <= n` でないとき, `a` が0のとき
+    pub fn with_first_root(n: usize, d: usize, a: T, b: usize) -> Self {
+        if 2 > d || d > n {
+            panic!("dの範囲が2 <= d <= n でない")
+        }
+        if a.is_zero() {
+            panic!("aが0")
+        }
+        Code { n, d, a, b }
+    }
+    /// 符号長
+    pub fn length(&self) -> usize {
+        self.n
+    }
+    /// 設計距離
+    pub fn distance(&self) -> usize {
+        self.d
+    }
+    /// 情報の長さ `n - d + 1`
+    pub fn dimension(&self) -> usize {
+        self.n - self.d + 1
+    }
+    /// 零点を定める体の元
+    pub fn root(&self) -> &T {
+        &self.a
+    }
+    /// 最初の零点の指数
+    pub fn first_root(&self) -> usize {
+        self.b
+    }
+    /// 零点 a^b, a^(b+1), ..., a^(b+d-2)
+    pub fn zeros(&self) -> Vec<T> {
+        let mut x = pow(&self.a, self.b);
+        let mut zeros = Vec::with_capacity(self.d - 1);
+        for _ in 0..self.d - 1 {
+            zeros.push(x.clone());
+            x *= self.a.clone();
+        }
+        zeros
+    }
+    /// 生成多項式 g(x) = (x - a^b)(x - a^(b+1))...(x - a^(b+d-2))
+    pub fn generator_polynomial(&self) -> Manipulative<T> {
+        let one = self.a.one_like();
+        let mut g = Manipulative::new(vec![one.clone()]);
+        for z in self.zeros() {
+            g *= &Manipulative::new(vec![-z, one.clone()]);
+        }
+        g
+    }
+    /// 受信語 `y` を多項式にする. 足りない記号は0とみなす.
+    /// # Panics
+    /// `y` の長さがnより大きいとき
+    pub(crate) fn received(&self, y: &[T]) -> Manipulative<T> {
+        if y.len() > self.n {
+            panic!("受信語の長さがnより大きい")
+        }
+        if y.is_empty() {
+            return Manipulative::new(vec![self.a.zero_like()]);
+        }
+        Manipulative::new(y.to_vec())
+    }
+}
+pub(crate) fn pow<T: FieldElement>(a: &T, mut exp: usize) -> T {
+    let mut base = a.clone();
+    let mut acc = a.one_like();
+    while exp > 0 {
+        if exp & 1 == 1 {
+            acc *= base.clone();
+        }
+        base *= base.clone();
+        exp >>= 1;
+    }
+    acc
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gf2m::Gf2m;
+    use crate::modulo::Field;
+    use crate::traits::Ring;
+
+    #[test]
+    fn generator_test() {
+        let code = Code::with_first_root(10, 5, Field::new(2, 11), 3);
+        let g = code.generator_polynomial();
+        assert_eq!(g.deg(), Some(4));
+        for i in 0..10 {
+            let x = pow(&Field::new(2, 11), i);
+            assert_eq!(g.assign(x).is_zero(), (3..7).contains(&i));
+        }
+        assert_eq!(code.dimension(), 6);
+    }
+    #[test]
+    fn first_root_test() {
+        // CCSDSと同じ GF(256) = GF(2)[x] / (x^8 + x^7 + x^2 + x + 1) 上で a = x^11, b = 112
+        let one = Gf2m::try_new(1, 0x187).unwrap();
+        let a = one.root().pow(11);
+        let msg: Vec<_> = (0..223).map(|i| one.new_like(i * 91 % 256)).collect();
+        for &b in &[0, 1, 112] {
+            let code = Code::with_first_root(255, 33, a.clone(), b);
+            for c in [code.encrypt(&msg), code.encrypt_systematic(&msg)] {
+                for z in code.zeros() {
+                    assert!(c.assign(z).is_zero());
+                }
+                let mut y = c.factors_padded(255);
+                let errors: Vec<_> = (0..16).map(|i| (i * 15 + 2, a.pow(i as u64))).collect();
+                for (i, e) in &errors {
+                    y[*i] += e.clone();
+                }
+                for w in [
+                    code.euclid_decrypt(&y),
+                    code.berlekamp_massey_decrypt(&y),
+                    code.pgz_decrypt(&y),
+                ] {
+                    let w = w.unwrap();
+                    assert_eq!(w.codeword, c);
+                    let values: Vec<_> = errors.iter().map(|(_, e)| e.clone()).collect();
+                    assert_eq!(w.error_values, values);
+                }
+                // 13誤りと6消失
+                let mut y = c.factors_padded(255);
+                for (i, e) in &errors[..13] {
+                    y[*i] += e.clone();
+                }
+                let erasures = [0, 1, 3, 5, 7, 11];
+                for &j in &erasures {
+                    y[j] = one.zero_like();
+                }
+                let w = code.euclid_decrypt_erasures(&y, &erasures).unwrap();
+                assert_eq!(w.codeword, c);
+            }
+        }
+    }
+    #[test]
+    #[should_panic(expected = "dの範囲")]
+    fn distance_test() {
+        Code::new(4, 5, Field::new(2, 5));
+    }
+}
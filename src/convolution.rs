@@ -0,0 +1,381 @@
+//! 多項式の係数列の積.
+//!
+//! 短いときは筆算, 長いときはKaratsuba法を使う. `Field` はさらに数論変換 (NTT) を使う.
+use super::modulo::{add_mod, is_prime, mul_mod, pow_mod, sub_mod, Field};
+use super::traits::Ring;
+use super::zmod::factorize;
+use std::any::Any;
+
+/// これより短い列は筆算で掛ける
+const KARATSUBA_THRESHOLD: usize = 32;
+/// `Field` の係数列がこれより短いときは数論変換を使わない
+const NTT_THRESHOLD: usize = 64;
+/// 中国剰余定理で使う, 2^21 次の1の冪根を持つ素数
+const NTT_PRIMES: [u64; 8] = [
+    998_244_353,
+    167_772_161,
+    469_762_049,
+    754_974_721,
+    1_004_535_809,
+    985_661_441,
+    2_013_265_921,
+    1_811_939_329,
+];
+/// `NTT_PRIMES` の原始根
+const NTT_ROOTS: [u64; 8] = [3, 3, 3, 11, 3, 3, 31, 13];
+/// `NTT_PRIMES` で計算できる長さの上限
+const NTT_MAX_LEN: usize = 1 << 21;
+
+/// 係数列 (次数の低い順, どちらも空でない) の積.
+/// `Field` の係数で長くて数論変換の方が速いと見積もられるときは数論変換, それ以外はKaratsuba法.
+pub(crate) fn multiply<T: Ring>(lhs: &[T], rhs: &[T]) -> Vec<T> {
+    if lhs.len().min(rhs.len()) >= NTT_THRESHOLD {
+        let as_field = |v: &T| (v as &dyn Any).downcast_ref::<Field>().copied();
+        let l: Option<Vec<Field>> = lhs.iter().map(as_field).collect();
+        let r: Option<Vec<Field>> = rhs.iter().map(as_field).collect();
+        if let Some(product) = l.zip(r).and_then(|(l, r)| multiply_field(&l, &r)) {
+            let product: Box<dyn Any> = Box::new(product);
+            return *product.downcast::<Vec<T>>().unwrap();
+        }
+    }
+    karatsuba(lhs, rhs)
+}
+/// `Field` の係数列の積を数論変換で求める. 法が揃っていないときや計算できないときは `None`
+fn multiply_field(lhs: &[Field], rhs: &[Field]) -> Option<Vec<Field>> {
+    let n = lhs[0].modulus();
+    if lhs.iter().chain(rhs.iter()).any(|v| v.modulus() != n) {
+        return None;
+    }
+    let l: Vec<u64> = lhs.iter().map(|v| v.value()).collect();
+    let r: Vec<u64> = rhs.iter().map(|v| v.value()).collect();
+    let product = multiply_mod(&l, &r, n)?;
+    Some(
+        product
+            .into_iter()
+            .map(|v| Field::from_reduced(v, n))
+            .collect(),
+    )
+}
+/// 筆算
+pub(crate) fn schoolbook<T: Ring>(lhs: &[T], rhs: &[T]) -> Vec<T> {
+    let mut result = vec![lhs[0].zero_like(); lhs.len() + rhs.len() - 1];
+    for (i, l) in lhs.iter().enumerate() {
+        for (j, r) in rhs.iter().enumerate() {
+            unsafe {
+                *result.get_unchecked_mut(i + j) += l.clone() * r.clone();
+            }
+        }
+    }
+    result
+}
+/// Karatsuba法. 短いときは筆算.
+fn karatsuba<T: Ring>(lhs: &[T], rhs: &[T]) -> Vec<T> {
+    if lhs.len().min(rhs.len()) < KARATSUBA_THRESHOLD {
+        return schoolbook(lhs, rhs);
+    }
+    let (lhs, rhs) = if lhs.len() < rhs.len() {
+        (rhs, lhs)
+    } else {
+        (lhs, rhs)
+    };
+    let zero = lhs[0].zero_like();
+    let mut result = vec![zero; lhs.len() + rhs.len() - 1];
+    let m = lhs.len() / 2;
+    if rhs.len() <= m {
+        // 長さが大きく違うときは長い方だけ分ける
+        for (k, chunk) in lhs.chunks(m).enumerate() {
+            for (i, v) in karatsuba(chunk, rhs).into_iter().enumerate() {
+                result[k * m + i] += v;
+            }
+        }
+        return result;
+    }
+    let (l0, l1) = lhs.split_at(m);
+    let (r0, r1) = rhs.split_at(m);
+    let z0 = karatsuba(l0, r0);
+    let z2 = karatsuba(l1, r1);
+    let mut z1 = karatsuba(&add(l0, l1), &add(r0, r1));
+    for (i, v) in z0.iter().enumerate() {
+        z1[i] -= v.clone();
+    }
+    for (i, v) in z2.iter().enumerate() {
+        z1[i] -= v.clone();
+    }
+    for (i, v) in z0.into_iter().enumerate() {
+        result[i] += v;
+    }
+    for (i, v) in z1.into_iter().enumerate() {
+        result[i + m] += v;
+    }
+    for (i, v) in z2.into_iter().enumerate() {
+        result[i + 2 * m] += v;
+    }
+    result
+}
+fn add<T: Ring>(a: &[T], b: &[T]) -> Vec<T> {
+    let (a, b) = if a.len() < b.len() { (b, a) } else { (a, b) };
+    let mut result = a.to_vec();
+    for (l, r) in result.iter_mut().zip(b.iter()) {
+        *l += r.clone();
+    }
+    result
+}
+/// 数論変換での掛け方
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Plan {
+    /// 原始根 `g` で `n` を法として直接変換する
+    Direct(u64),
+    /// `NTT_PRIMES` のはじめのいくつかで変換して中国剰余定理で復元する
+    Crt(usize),
+}
+/// `n` を法として0以上 `n` 未満の係数列を掛ける.
+/// Karatsuba法の方が速いと見積もられるときや, 長すぎて計算できないときは `None`
+fn multiply_mod(lhs: &[u64], rhs: &[u64], n: u64) -> Option<Vec<u64>> {
+    let p = plan(n, lhs.len() + rhs.len() - 1)?;
+    if !ntt_wins(p, lhs.len(), rhs.len()) {
+        return None;
+    }
+    Some(convolve(lhs, rhs, n, p))
+}
+/// 長さ `len` の積の掛け方を決める. 長すぎるときは `None`
+///
+/// `n` が素数で `n - 1` が十分大きい2のべきで割り切れるときは `n` を法とするNTT,
+/// そうでないときは `NTT_PRIMES` のいくつかでNTTをして中国剰余定理で復元する.
+fn plan(n: u64, len: usize) -> Option<Plan> {
+    let size = len.next_power_of_two();
+    if n > 2 && (n - 1).is_multiple_of(size as u64) && is_prime(n) {
+        return Some(Plan::Direct(
+            match NTT_PRIMES.iter().position(|&p| p == n) {
+                Some(i) => NTT_ROOTS[i],
+                None => primitive_root(n),
+            },
+        ));
+    }
+    if size > NTT_MAX_LEN {
+        return None;
+    }
+    // 積の係数は len (n-1)^2 未満なので, 素数の積がそれを超えるまで使う
+    let bits = 2 * (64 - (n - 1).leading_zeros()) + (64 - (len as u64).leading_zeros());
+    let mut count = 0;
+    let mut product_bits = 0;
+    while product_bits <= bits {
+        product_bits += 63 - NTT_PRIMES[count].leading_zeros();
+        count += 1;
+    }
+    Some(Plan::Crt(count))
+}
+/// 長さ `l`, `r` の列を `plan` で掛ける方がKaratsuba法より速いと見積もられるか
+fn ntt_wins(plan: Plan, l: usize, r: usize) -> bool {
+    let len = l + r - 1;
+    let size = len.next_power_of_two();
+    let cost = match plan {
+        Plan::Direct(_) => ntt_cost(size, 1) + len as f64,
+        // Garnerの算法は係数ごとに count^2 回くらい掛ける
+        Plan::Crt(count) => ntt_cost(size, count) + (len * count * count) as f64,
+    };
+    cost < karatsuba_cost(l, r)
+}
+fn convolve(lhs: &[u64], rhs: &[u64], n: u64, plan: Plan) -> Vec<u64> {
+    let len = lhs.len() + rhs.len() - 1;
+    let count = match plan {
+        Plan::Direct(g) => return ntt_multiply(lhs, rhs, n, g, len),
+        Plan::Crt(count) => count,
+    };
+    let residues: Vec<Vec<u64>> = NTT_PRIMES[..count]
+        .iter()
+        .zip(NTT_ROOTS.iter())
+        .map(|(&p, &g)| {
+            let l: Vec<u64> = lhs.iter().map(|v| v % p).collect();
+            let r: Vec<u64> = rhs.iter().map(|v| v % p).collect();
+            ntt_multiply(&l, &r, p, g, len)
+        })
+        .collect();
+    let inverses = garner_inverses(count);
+    (0..len)
+        .map(|i| {
+            let r: Vec<u64> = residues.iter().map(|r| r[i]).collect();
+            garner(&r, &inverses, n)
+        })
+        .collect()
+}
+/// 長さ `l`, `r` のKaratsuba法で掛け算と足し算をする回数の見積もり
+fn karatsuba_cost(l: usize, r: usize) -> f64 {
+    let (l, r) = (l.max(r) as f64, l.min(r) as f64);
+    // 掛け算 r^log2(3) 回を l / r 回, 足し算はその倍くらい
+    2.0 * l / r * r.powf(3f64.log2())
+}
+/// 長さ `size` のNTTでの掛け算を `count` 個の素数でする回数の見積もり
+fn ntt_cost(size: usize, count: usize) -> f64 {
+    // 順変換2回と逆変換1回
+    (3 * count * size * size.trailing_zeros() as usize) as f64
+}
+/// `NTT_PRIMES[i]` を法とした `NTT_PRIMES[0] ... NTT_PRIMES[i - 1]` の逆元 (i < `count`)
+fn garner_inverses(count: usize) -> Vec<u64> {
+    (0..count)
+        .map(|i| {
+            let p = NTT_PRIMES[i];
+            let base = NTT_PRIMES[..i]
+                .iter()
+                .fold(1, |acc, &m| mul_mod(acc, m % p, p));
+            pow_mod(base, p - 2, p)
+        })
+        .collect()
+}
+/// Garnerの算法で, `NTT_PRIMES[i]` を法として `residues[i]` と合同な数を `n` で割った余りを求める.
+/// `inverses` は `garner_inverses` の結果.
+fn garner(residues: &[u64], inverses: &[u64], n: u64) -> u64 {
+    // x = v_0 + v_1 m_0 + v_2 m_0 m_1 + ...
+    let mut digits: Vec<u64> = Vec::with_capacity(residues.len());
+    for (i, &r) in residues.iter().enumerate() {
+        let p = NTT_PRIMES[i];
+        let mut x = 0;
+        let mut base = 1;
+        for (j, &v) in digits.iter().enumerate() {
+            x = add_mod(x, mul_mod(v % p, base, p), p);
+            base = mul_mod(base, NTT_PRIMES[j] % p, p);
+        }
+        digits.push(mul_mod(sub_mod(r, x, p), inverses[i], p));
+    }
+    let mut x = 0;
+    let mut base = 1 % n;
+    for (j, &v) in digits.iter().enumerate() {
+        x = add_mod(x, mul_mod(v % n, base, n), n);
+        base = mul_mod(base, NTT_PRIMES[j] % n, n);
+    }
+    x
+}
+/// 素数 `p` の原始根
+fn primitive_root(p: u64) -> u64 {
+    let factors = factorize(p - 1);
+    (2..)
+        .find(|&g| {
+            factors
+                .iter()
+                .all(|&(q, _)| pow_mod(g, (p - 1) / q, p) != 1)
+        })
+        .unwrap()
+}
+fn ntt_multiply(lhs: &[u64], rhs: &[u64], p: u64, g: u64, len: usize) -> Vec<u64> {
+    let size = len.next_power_of_two();
+    let mut a = lhs.to_vec();
+    let mut b = rhs.to_vec();
+    a.resize(size, 0);
+    b.resize(size, 0);
+    ntt(&mut a, p, g, false);
+    ntt(&mut b, p, g, false);
+    for (x, y) in a.iter_mut().zip(b.iter()) {
+        *x = mul_mod(*x, *y, p);
+    }
+    ntt(&mut a, p, g, true);
+    a.truncate(len);
+    a
+}
+/// 長さが2のべきの列の数論変換. `invert` のときは逆変換.
+fn ntt(a: &mut [u64], p: u64, g: u64, invert: bool) {
+    let n = a.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            a.swap(i, j);
+        }
+    }
+    let mut len = 2;
+    while len <= n {
+        let mut w = pow_mod(g, (p - 1) / len as u64, p);
+        if invert {
+            w = pow_mod(w, p - 2, p);
+        }
+        for chunk in a.chunks_mut(len) {
+            let (lo, hi) = chunk.split_at_mut(len / 2);
+            let mut wk = 1;
+            for (u, v) in lo.iter_mut().zip(hi.iter_mut()) {
+                let t = mul_mod(*v, wk, p);
+                *v = sub_mod(*u, t, p);
+                *u = add_mod(*u, t, p);
+                wk = mul_mod(wk, w, p);
+            }
+        }
+        len <<= 1;
+    }
+    if invert {
+        let inv = pow_mod(n as u64, p - 2, p);
+        for x in a.iter_mut() {
+            *x = mul_mod(*x, inv, p);
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::Rng;
+
+    #[test]
+    fn karatsuba_test() {
+        let mut rng = Rng(0x5851_f42d_4c95_7f2d);
+        for &(l, r) in &[(1, 1), (31, 40), (64, 64), (100, 37), (300, 7), (257, 190)] {
+            let a: Vec<i64> = (0..l).map(|_| rng.next(2000) as i64 - 1000).collect();
+            let b: Vec<i64> = (0..r).map(|_| rng.next(2000) as i64 - 1000).collect();
+            assert_eq!(karatsuba(&a, &b), schoolbook(&a, &b));
+        }
+    }
+    #[test]
+    fn multiply_mod_test() {
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+        // NTT向きの素数, 一般の素数, 合成数, 64ビットの素数
+        for &n in &[
+            998_244_353,
+            1_000_000_007,
+            1_000_000_000,
+            (1 << 61) - 1,
+            u64::MAX - 58,
+        ] {
+            for &(l, r) in &[(1, 1), (100, 100), (513, 300), (2000, 1)] {
+                let a: Vec<u64> = (0..l).map(|_| rng.next(n)).collect();
+                let b: Vec<u64> = (0..r).map(|_| rng.next(n)).collect();
+                let mut expected = vec![0; l + r - 1];
+                for (i, x) in a.iter().enumerate() {
+                    for (j, y) in b.iter().enumerate() {
+                        expected[i + j] = add_mod(expected[i + j], mul_mod(*x, *y, n), n);
+                    }
+                }
+                let p = plan(n, l + r - 1).unwrap();
+                assert_eq!(convolve(&a, &b, n, p), expected);
+            }
+        }
+    }
+    #[test]
+    fn multiply_test() {
+        let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+        let f = |v: u64| Field::new(v as i64, 998_244_353);
+        let a: Vec<Field> = (0..2000).map(|_| f(rng.next(998_244_353))).collect();
+        let b: Vec<Field> = (0..2000).map(|_| f(rng.next(998_244_353))).collect();
+        assert_eq!(multiply_field(&a, &b), Some(schoolbook(&a, &b)));
+        assert_eq!(multiply(&a, &b), schoolbook(&a, &b));
+        // 法が揃っていないときは数論変換を使わない
+        let mut c = b.clone();
+        c[0] = Field::new(1, 7);
+        assert_eq!(multiply_field(&a, &c), None);
+    }
+    #[test]
+    fn plan_test() {
+        for (&p, &g) in NTT_PRIMES.iter().zip(NTT_ROOTS.iter()) {
+            assert_eq!(primitive_root(p), g);
+        }
+        assert_eq!(plan(998_244_353, 4000), Some(Plan::Direct(3)));
+        assert_eq!(plan(1_000_000_007, 4000), Some(Plan::Crt(3)));
+        let p62 = 4_611_686_018_427_387_847;
+        assert_eq!(plan(p62, 4000), Some(Plan::Crt(5)));
+        assert_eq!(plan(p62, NTT_MAX_LEN + 1), None);
+        // NTT向きの素数は短くても数論変換, 62ビットの素数はかなり長くないとKaratsuba法
+        assert!(ntt_wins(Plan::Direct(3), 2000, 2000));
+        assert!(!ntt_wins(Plan::Crt(5), 2000, 2000));
+        assert!(!ntt_wins(Plan::Crt(5), 100_000, 64));
+        assert!(ntt_wins(Plan::Crt(5), 1 << 19, 1 << 19));
+    }
+}
@@ -1,88 +1,471 @@
+use super::code::{pow, Code};
 use super::manipulative::Manipulative;
 use super::modulo::Field;
+use super::traits::FieldElement;
+use std::error;
+use std::fmt;
+
+/// 復号の結果.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodeResult<T> {
+    /// 訂正した符号語
+    pub codeword: Manipulative<T>,
+    /// 誤りの位置(昇順)
+    pub error_positions: Vec<usize>,
+    /// 誤りの値(`error_positions` と同じ順). 受信語 = 符号語 + 誤り
+    pub error_values: Vec<T>,
+    /// 誤り位置多項式. 定数項を1に正規化してある.
+    pub locator: Manipulative<T>,
+    /// 誤り評価多項式
+    pub evaluator: Manipulative<T>,
+}
+/// 復号の失敗.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// 誤りが訂正能力を超えている
+    TooManyErrors,
+    /// リスト復号の半径がJohnson半径を超えている
+    RadiusTooLarge,
+}
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::TooManyErrors => write!(f, "too many errors to correct"),
+            DecodeError::RadiusTooLarge => write!(f, "radius exceeds the Johnson radius"),
+        }
+    }
+}
+impl error::Error for DecodeError {}
 
 /// ユークリッド復号する.
-/// p: 体の標数, n: 多項式の最大次数 + 1; C={f(a^i) (0<=i<=d-1) = 0}を線形符号とする.
+/// p: 体の標数(素数), n: 符号長; C={f(a^i) (1<=i<=d-1) = 0}を線形符号とし,
+/// シンドローム s_i = y(a^i) (1<=i<=d-1) から復号する. 零点を a^b, ..., a^(b+d-2) にするときは
+/// `Code::with_first_root` を使う.
 /// y: 復号したい多項式のスライス(次数の低い順)
-/// p: 素数, aの位数の判定は行わない.
+/// aの位数の判定は行わない. 一般の体では `euclid_decrypt_generic` を使う.
 /// # Panics
-/// `2 <= d <= n` でないとき
+/// `2 <= d <= n` でないとき, `y` の長さがnより大きいとき
 /// # Example
 /// ```
 /// # use finite_field::manipulative::Manipulative;
 /// # use finite_field::modulo::Field;
 /// # use finite_field::decryption::euclid_decrypt;
-/// let w = euclid_decrypt(5, 4, 3, 2, &[1, 0, 2, 1]); // 1 + 2 x^2 + x^3
+/// let w = euclid_decrypt(5, 4, 3, 2, &[1, 0, 2, 1]).unwrap(); // 1 + 2 x^2 + x^3
 /// assert_eq!(
-///     w,
+///     w.codeword,
 ///     Manipulative::new([4, 0, 2, 1].iter().map(|v| Field::new(*v, 5)).collect()) // 4 + 2 x^2 + x^3
 ///);
+/// assert_eq!(w.error_positions, [0]);
+/// assert_eq!(w.error_values, [Field::new(2, 5)]);
+/// ```
+pub fn euclid_decrypt(
+    p: u64,
+    n: usize,
+    d: usize,
+    a: i64,
+    y: &[i64],
+) -> Result<DecodeResult<Field>, DecodeError> {
+    let y: Vec<Field> = y.iter().map(|v| Field::new(*v, p)).collect();
+    euclid_decrypt_generic(n, d, Field::new(a, p), &y)
+}
+/// 任意の有限体上でユークリッド復号する.
+/// n: 符号長; C={f(a^i) (1<=i<=d-1) = 0}を線形符号とする.
+/// a: 符号を定める体の元, y: 復号したい多項式のスライス(次数の低い順)
+/// aの位数の判定は行わない. 零点を a^b から始めるときは `Code` を使う.
+/// 誤り位置多項式の根の数が次数と一致しないときは `DecodeError::TooManyErrors`
+/// # Panics
+/// `2 <= d <= n` でないとき, `y` の長さがnより大きいとき, `a` が0のとき
+/// # Example
+/// ```
+/// # use finite_field::manipulative::Manipulative;
+/// # use finite_field::gf2m::Gf2m;
+/// # use finite_field::decryption::euclid_decrypt_generic;
+/// // GF(16) 上の (15, 11) 符号の零符号語に2つの誤りを加える
+/// let zero = Gf2m::try_new(0, 0b10011).unwrap();
+/// let a = zero.root();
+/// let mut y = vec![zero.clone(); 15];
+/// y[3] = a.clone();
+/// y[10] = a.pow(2);
+/// let w = euclid_decrypt_generic(15, 5, a.clone(), &y).unwrap();
+/// assert_eq!(w.codeword, Manipulative::new(vec![zero; 15]));
+/// assert_eq!(w.error_positions, [3, 10]);
+/// assert_eq!(w.error_values, [a.clone(), a.pow(2)]);
+/// ```
+pub fn euclid_decrypt_generic<T: FieldElement>(
+    n: usize,
+    d: usize,
+    a: T,
+    y: &[T],
+) -> Result<DecodeResult<T>, DecodeError> {
+    Code::new(n, d, a).euclid_decrypt(y)
+}
+/// 消失位置を与えてユークリッド復号する. 誤りの数 e, 消失の数 s について 2e + s < d なら訂正できる.
+/// 消失した記号の受信値は任意でよい. その他の引数は `euclid_decrypt` と同じ.
+/// # Panics
+/// `2 <= d <= n` でないとき, `y` の長さがnより大きいとき, 消失位置がn以上のとき
+/// # Example
+/// ```
+/// # use finite_field::manipulative::Manipulative;
+/// # use finite_field::modulo::Field;
+/// # use finite_field::decryption::euclid_decrypt_erasures;
+/// // 1 + 10x + x^2 + 8x^3 + 3x^4 + 2x^5 + 5x^6 + x^7 の x^0, x^1 が消失し, x^6 に誤り
+/// let y = [0, 0, 1, 8, 3, 2, 0, 1];
+/// let w = euclid_decrypt_erasures(11, 8, 5, 2, &y, &[0, 1]).unwrap();
+/// assert_eq!(
+///     w.codeword,
+///     Manipulative::new([1, 10, 1, 8, 3, 2, 5, 1].iter().map(|v| Field::new(*v, 11)).collect())
+/// );
+/// assert_eq!(w.error_positions, [0, 1, 6]);
+/// ```
+pub fn euclid_decrypt_erasures(
+    p: u64,
+    n: usize,
+    d: usize,
+    a: i64,
+    y: &[i64],
+    erasures: &[usize],
+) -> Result<DecodeResult<Field>, DecodeError> {
+    let y: Vec<Field> = y.iter().map(|v| Field::new(*v, p)).collect();
+    euclid_decrypt_erasures_generic(n, d, Field::new(a, p), &y, erasures)
+}
+/// 任意の有限体上で消失位置を与えてユークリッド復号する.
+/// 結果の `error_positions` と `error_values` は消失位置を含む(値が0のこともある).
+/// 消失の数が `d - 1` を超えるときは `DecodeError::TooManyErrors`
+/// # Panics
+/// `2 <= d <= n` でないとき, `y` の長さがnより大きいとき, 消失位置がn以上のとき, `a` が0のとき
+pub fn euclid_decrypt_erasures_generic<T: FieldElement>(
+    n: usize,
+    d: usize,
+    a: T,
+    y: &[T],
+    erasures: &[usize],
+) -> Result<DecodeResult<T>, DecodeError> {
+    Code::new(n, d, a).euclid_decrypt_erasures(y, erasures)
+}
+/// Berlekamp-Massey法で復号する. 符号と引数は `euclid_decrypt` と同じ.
+/// # Panics
+/// `2 <= d <= n` でないとき, `y` の長さがnより大きいとき
+/// # Example
+/// ```
+/// # use finite_field::decryption::{berlekamp_massey_decrypt, euclid_decrypt};
+/// let y = [1, -1, 1, 0, 3, 2, 0, 1];
+/// let w = berlekamp_massey_decrypt(11, 8, 5, 2, &y).unwrap();
+/// assert_eq!(w.codeword, euclid_decrypt(11, 8, 5, 2, &y).unwrap().codeword);
+/// assert_eq!(w.error_positions, [3, 6]);
 /// ```
-pub fn euclid_decrypt(p: u64, n: usize, d: usize, a: i64, y: &[i64]) -> Manipulative<Field> {
-    if 2 > d || d > n {
-        panic!("dの範囲が2 <= d <= n でない")
+pub fn berlekamp_massey_decrypt(
+    p: u64,
+    n: usize,
+    d: usize,
+    a: i64,
+    y: &[i64],
+) -> Result<DecodeResult<Field>, DecodeError> {
+    let y: Vec<Field> = y.iter().map(|v| Field::new(*v, p)).collect();
+    berlekamp_massey_decrypt_generic(n, d, Field::new(a, p), &y)
+}
+/// 任意の有限体上でBerlekamp-Massey法で復号する. 符号と引数は `euclid_decrypt_generic` と同じ.
+/// # Panics
+/// `2 <= d <= n` でないとき, `y` の長さがnより大きいとき, `a` が0のとき
+pub fn berlekamp_massey_decrypt_generic<T: FieldElement>(
+    n: usize,
+    d: usize,
+    a: T,
+    y: &[T],
+) -> Result<DecodeResult<T>, DecodeError> {
+    Code::new(n, d, a).berlekamp_massey_decrypt(y)
+}
+impl<T: FieldElement> Code<T> {
+    /// ユークリッド復号する. 誤り位置多項式の根の数が次数と一致しないときは `DecodeError::TooManyErrors`
+    /// # Panics
+    /// `y` の長さがnより大きいとき
+    pub fn euclid_decrypt(&self, y: &[T]) -> Result<DecodeResult<T>, DecodeError> {
+        self.euclid_decrypt_erasures(y, &[])
     }
-    let y = y.iter().map(|v| Field::new(*v, p)).collect();
-    let mut y = Manipulative::new(y);
+    /// 消失位置を与えてユークリッド復号する. 誤りの数 e, 消失の数 s について 2e + s < d なら訂正できる.
+    /// 結果の `error_positions` と `error_values` は消失位置を含む(値が0のこともある).
+    /// 消失の数が `d - 1` を超えるときは `DecodeError::TooManyErrors`
+    /// # Panics
+    /// `y` の長さがnより大きいとき, 消失位置がn以上のとき
+    pub fn euclid_decrypt_erasures(
+        &self,
+        y: &[T],
+        erasures: &[usize],
+    ) -> Result<DecodeResult<T>, DecodeError> {
+        let (n, d, a) = (self.length(), self.distance(), self.root());
+        let mut erasures = erasures.to_vec();
+        erasures.sort_unstable();
+        erasures.dedup();
+        if erasures.last().is_some_and(|&j| j >= n) {
+            panic!("消失位置がn以上")
+        }
+        let s = erasures.len();
+        if s > d - 1 {
+            return Err(DecodeError::TooManyErrors);
+        }
+        let zero = a.zero_like();
+        let one = a.one_like();
+        let y = self.received(y);
+
+        // 消失位置多項式 Γ(x) = Π(1 - a^j x)
+        let mut gamma = Manipulative::new(vec![one.clone()]);
+        for &j in &erasures {
+            gamma *= &Manipulative::new(vec![one.clone(), -pow(a, j)]);
+        }
+
+        let mut t_m1 = Manipulative::new(vec![zero.clone()]);
+        let mut t_0 = Manipulative::new(vec![one.clone()]);
+
+        let mut r_m1 = vec![zero; d - 1];
+        r_m1.push(one);
+        let mut r_m1 = Manipulative::new(r_m1);
 
-    let mut t_m1 = Manipulative::new(vec![Field::new(0, p)]);
-    let mut t_0 = Manipulative::new(vec![Field::new(1, p)]);
+        // 修正シンドローム T(x) = Γ(x) S(x) mod x^(d-1)
+        let mut r_0 = (&gamma * &self.syndromes(&y)).factors().to_vec();
+        r_0.truncate(d - 1);
+        let mut r_0 = Manipulative::new(r_0);
 
-    let mut r_m1 = vec![Field::new(0, p); d - 1];
-    r_m1.push(Field::new(1, p));
-    let mut r_m1 = Manipulative::new(r_m1);
+        while r_0.deg().is_some_and(|deg| deg >= (d - 1 + s) / 2) {
+            let (q, new_r_0) = r_m1.divide_by(&r_0).unwrap();
+            r_m1 = std::mem::replace(&mut r_0, new_r_0);
+            let new_t_0 = t_m1.clone() - &(&q * &t_0);
+            t_m1 = std::mem::replace(&mut t_0, new_t_0);
+        }
+        self.correct(y, &t_0 * &gamma, r_0)
+    }
+    /// Berlekamp-Massey法で復号する.
+    /// # Panics
+    /// `y` の長さがnより大きいとき
+    pub fn berlekamp_massey_decrypt(&self, y: &[T]) -> Result<DecodeResult<T>, DecodeError> {
+        let (d, a) = (self.distance(), self.root());
+        let zero = a.zero_like();
+        let one = a.one_like();
+        let y = self.received(y);
+        let s = self.syndromes(&y).factors_padded(d - 1);
 
-    let mut r_0 = Vec::with_capacity(d - 2);
-    let mut _a = Field::new(1, p);
-    for _ in 0..d - 1 {
-        _a *= a;
-        r_0.push(y.assign(_a));
+        // c: 接続多項式, b: 最後に長さが変わる前のc
+        let mut c = vec![one.clone()];
+        let mut b = vec![one.clone()];
+        let mut l = 0;
+        let mut m = 1;
+        let mut last = one;
+        for k in 0..d - 1 {
+            let mut delta = s[k].clone();
+            for i in 1..=l.min(c.len() - 1) {
+                delta += c[i].clone() * s[k - i].clone();
+            }
+            if delta.is_zero() {
+                m += 1;
+                continue;
+            }
+            let coef = delta.clone() / last.clone();
+            let prev = c.clone();
+            if c.len() < b.len() + m {
+                c.resize(b.len() + m, zero.clone());
+            }
+            for (i, b_i) in b.iter().enumerate() {
+                c[i + m] -= coef.clone() * b_i.clone();
+            }
+            if 2 * l <= k {
+                l = k + 1 - l;
+                b = prev;
+                last = delta;
+                m = 1;
+            } else {
+                m += 1;
+            }
+        }
+        if 2 * l > d - 1 {
+            return Err(DecodeError::TooManyErrors);
+        }
+        let locator = Manipulative::new(c);
+        // ω(x) = σ(x) S(x) mod x^(d-1)
+        let mut evaluator = (&locator * &Manipulative::new(s.to_vec()))
+            .factors()
+            .to_vec();
+        evaluator.truncate(d - 1);
+        self.correct(y, locator, Manipulative::new(evaluator))
     }
-    let mut r_0 = Manipulative::new(r_0);
+    /// Peterson-Gorenstein-Zierler法で復号する.
+    /// シンドローム行列の階数から誤りの数を求め, 連立一次方程式を解いて誤り位置多項式を得る.
+    /// 小さい符号の検算向けで, 計算量は O(d^3).
+    /// # Panics
+    /// `y` の長さがnより大きいとき
+    /// # Example
+    /// ```
+    /// # use finite_field::code::Code;
+    /// # use finite_field::decryption::euclid_decrypt;
+    /// # use finite_field::modulo::Field;
+    /// let y = [1, -1, 1, 0, 3, 2, 0, 1];
+    /// let code = Code::new(8, 5, Field::new(2, 11));
+    /// let w = code
+    ///     .pgz_decrypt(&y.iter().map(|v| Field::new(*v, 11)).collect::<Vec<_>>())
+    ///     .unwrap();
+    /// assert_eq!(w.codeword, euclid_decrypt(11, 8, 5, 2, &y).unwrap().codeword);
+    /// assert_eq!(w.error_positions, [3, 6]);
+    /// ```
+    pub fn pgz_decrypt(&self, y: &[T]) -> Result<DecodeResult<T>, DecodeError> {
+        let d = self.distance();
+        let t = (d - 1) / 2;
+        let y = self.received(y);
+        let s = self.syndromes(&y).factors_padded(d - 1);
+
+        // 誤りの数 ν はシンドローム行列 (s_(i+j)) (0 <= i, j < t) の階数
+        let mut m: Vec<Vec<T>> = (0..t).map(|i| s[i..i + t].to_vec()).collect();
+        let nu = row_reduce(&mut m, t);
 
-    if r_0.deg() == -1 {
-        return y;
+        // s_(i+ν) + σ_1 s_(i+ν-1) + ... + σ_ν s_i = 0 (0 <= i < ν) を解く
+        let mut m: Vec<Vec<T>> = (0..nu)
+            .map(|i| {
+                let mut row = s[i..i + nu].to_vec();
+                row.push(-s[i + nu].clone());
+                row
+            })
+            .collect();
+        if row_reduce(&mut m, nu) != nu {
+            return Err(DecodeError::TooManyErrors);
+        }
+        // m[j][ν] = σ_(ν-j)
+        let mut locator = vec![self.root().one_like()];
+        locator.extend(m.into_iter().rev().map(|row| row[nu].clone()));
+        let locator = Manipulative::new(locator);
+        // ω(x) = σ(x) S(x) mod x^(d-1)
+        let mut evaluator = (&locator * &Manipulative::new(s.to_vec()))
+            .factors()
+            .to_vec();
+        evaluator.truncate(d - 1);
+        self.correct(y, locator, Manipulative::new(evaluator))
     }
-    loop {
-        let (q, new_r_0) = r_m1.divide_by(&r_0).unwrap();
-        r_m1 = std::mem::replace(&mut r_0, new_r_0);
-        let new_t_0 = t_m1.clone() - &(&q * &t_0);
-        t_m1 = std::mem::replace(&mut t_0, new_t_0);
-        if r_0.deg() <= (d as i64 - 1) / 2 - 1 {
-            break;
+    /// シンドローム多項式 S(x) = y(a^b) + y(a^(b+1)) x + ... + y(a^(b+d-2)) x^(d-2)
+    fn syndromes(&self, y: &Manipulative<T>) -> Manipulative<T> {
+        Manipulative::new(self.zeros().into_iter().map(|z| y.assign(z)).collect())
+    }
+    /// Chien探索で誤り位置多項式の根 a^(-i) (0 <= i < n) を探し, 位置 i を返す
+    fn chien_search(&self, locator: &Manipulative<T>) -> Vec<usize> {
+        let a_inv = self.root().inv().expect("aが0");
+        let mut _a = self.root().clone();
+        let mut positions = Vec::new();
+        for i in 0..self.length() {
+            _a *= a_inv.clone();
+            if locator.assign(_a.clone()).is_zero() {
+                positions.push(i);
+            }
+        }
+        positions
+    }
+    /// Forneyの公式 e_i = -ω(a^(-i)) / (a^(i(b-1)) σ'(a^(-i))) で誤りの値を求める
+    fn forney(
+        &self,
+        locator: &Manipulative<T>,
+        evaluator: &Manipulative<T>,
+        positions: &[usize],
+    ) -> Result<Vec<T>, DecodeError> {
+        let a = self.root();
+        let a_inv = a.inv().expect("aが0");
+        let locator_diff = locator.diff();
+        let mut values = Vec::with_capacity(positions.len());
+        for &i in positions {
+            let x = pow(&a_inv, i);
+            let diff = locator_diff.assign(x.clone());
+            if diff.is_zero() {
+                return Err(DecodeError::TooManyErrors);
+            }
+            // a^(i(1-b))
+            let scale = match self.first_root() {
+                0 => pow(a, i),
+                b => pow(&x, b - 1),
+            };
+            values.push(-(evaluator.assign(x) * scale / diff));
+        }
+        Ok(values)
+    }
+    /// 鍵方程式の解 (誤り位置多項式 `t`, 誤り評価多項式 `r`) から誤りを訂正する
+    fn correct(
+        &self,
+        mut y: Manipulative<T>,
+        t: Manipulative<T>,
+        r: Manipulative<T>,
+    ) -> Result<DecodeResult<T>, DecodeError> {
+        // 誤り位置多項式の定数項を1にする
+        let c = match t.factors()[0].inv() {
+            Some(c) => Manipulative::new(vec![c]),
+            None => return Err(DecodeError::TooManyErrors),
+        };
+        let locator = &t * &c;
+        let evaluator = &r * &c;
+
+        if evaluator.deg() >= locator.deg() {
+            return Err(DecodeError::TooManyErrors);
         }
+        let error_positions = self.chien_search(&locator);
+        if Some(error_positions.len()) != locator.deg() {
+            return Err(DecodeError::TooManyErrors);
+        }
+        let error_values = self.forney(&locator, &evaluator, &error_positions)?;
+        let mut err = vec![self.root().zero_like(); self.length()];
+        for (i, e) in error_positions.iter().zip(error_values.iter()) {
+            err[*i] = e.clone();
+        }
+        y -= &Manipulative::new(err);
+        Ok(DecodeResult {
+            codeword: y,
+            error_positions,
+            error_values,
+            locator,
+            evaluator,
+        })
     }
-    let mut _a = Field::new(a, p);
-    let a_inv = !Field::new(a, p);
-    let mut err = vec![Field::new(0, p); n];
-    for e in &mut err {
-        _a *= a_inv;
-        if t_0.assign(_a) == Field::new(0, p) {
-            *e -= r_0.assign(_a) / t_0.diff().assign(_a)
+}
+/// 行列 `m` の左 `cols` 列を掃き出して簡約階段形にし, 階数を返す
+fn row_reduce<T: FieldElement>(m: &mut [Vec<T>], cols: usize) -> usize {
+    let mut rank = 0;
+    for c in 0..cols {
+        let pivot = match (rank..m.len()).find(|&r| !m[r][c].is_zero()) {
+            Some(r) => r,
+            None => continue,
+        };
+        m.swap(rank, pivot);
+        let inv = m[rank][c].inv().unwrap();
+        for v in m[rank].iter_mut() {
+            *v *= inv.clone();
         }
+        let pivot_row = m[rank].clone();
+        for (r, row) in m.iter_mut().enumerate() {
+            if r == rank || row[c].is_zero() {
+                continue;
+            }
+            let coef = row[c].clone();
+            for (v, p) in row.iter_mut().zip(pivot_row.iter()) {
+                *v -= coef.clone() * p.clone();
+            }
+        }
+        rank += 1;
     }
-    y -= &Manipulative::new(err);
-    y
+    rank
 }
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::encryption::encrypt_generic;
+    use crate::test_util::Rng;
+    use crate::traits::Ring;
 
     #[test]
     fn decryption_test() {
-        let w = euclid_decrypt(5, 4, 3, 2, &[1, 0, 2, 1]);
+        let w = euclid_decrypt(5, 4, 3, 2, &[1, 0, 2, 1]).unwrap().codeword;
         assert_eq!(
             w,
             Manipulative::new([4, 0, 2, 1].iter().map(|v| Field::new(*v, 5)).collect())
         );
-        let w = euclid_decrypt(5, 4, 3, 2, &[4, 0, 2, 1]);
+        let w = euclid_decrypt(5, 4, 3, 2, &[4, 0, 2, 1]).unwrap().codeword;
         assert_eq!(
             w,
             Manipulative::new([4, 0, 2, 1].iter().map(|v| Field::new(*v, 5)).collect())
         );
-        let w = euclid_decrypt(11, 7, 5, 2, &[1, -1, 1, 0, 3, 2, 0, 1]);
+        let w = euclid_decrypt(11, 8, 5, 2, &[1, -1, 1, 0, 3, 2, 0, 1]).unwrap();
+        assert_eq!(w.error_positions, [3, 6]);
+        assert_eq!(w.error_values, [Field::new(3, 11), Field::new(6, 11)]);
         assert_eq!(
-            w,
+            w.codeword,
             Manipulative::new(
                 [1, 10, 1, 8, 3, 2, 5, 1]
                     .iter()
@@ -91,4 +474,231 @@ mod tests {
             )
         );
     }
+    fn check<T: FieldElement>(n: usize, d: usize, a: T, msg: &[T], errors: &[(usize, T)]) {
+        let c = encrypt_generic(n, d, a.clone(), msg);
+        let mut y = c.factors_padded(n);
+        for (i, e) in errors {
+            y[*i] += e.clone();
+        }
+        let w = euclid_decrypt_generic(n, d, a.clone(), &y).unwrap();
+        assert_eq!(w.codeword, c);
+        let positions: Vec<usize> = errors.iter().map(|(i, _)| *i).collect();
+        let values: Vec<T> = errors.iter().map(|(_, e)| e.clone()).collect();
+        assert_eq!(w.error_positions, positions);
+        assert_eq!(w.error_values, values);
+        assert_eq!(w.locator.deg(), Some(errors.len()));
+        assert!(w.locator.factors()[0] == a.one_like());
+    }
+    #[test]
+    fn generic_test() {
+        use crate::ext_field::ExtField;
+        use crate::fp::Fp;
+        use crate::gf2m::Gf2m;
+        use crate::montgomery::MontFp;
+
+        // GF(11) 上で位数10の元 2
+        let f = |v: i64| Fp::<11>::new(v);
+        let msg: Vec<_> = [3, 1, 4, 1, 5, 9].iter().map(|v| f(*v)).collect();
+        check(10, 5, f(2), &msg, &[(0, f(7)), (9, f(1))]);
+        let g = |v: i64| MontFp::<11>::new(v);
+        let msg: Vec<_> = [2, 7, 1, 8].iter().map(|v| g(*v)).collect();
+        check(10, 7, g(2), &msg, &[(2, g(3)), (5, g(10)), (6, g(4))]);
+
+        // GF(16) = GF(2)[x] / (x^4 + x + 1)
+        let one = Gf2m::try_new(1, 0b10011).unwrap();
+        let a = one.root();
+        let msg: Vec<_> = (0..9).map(|i| a.pow(i * 7)).collect();
+        check(
+            15,
+            7,
+            a.clone(),
+            &msg,
+            &[(0, a.clone()), (7, one), (14, a.pow(11))],
+        );
+
+        // GF(9) = GF(3)[x] / (x^2 + 2x + 2) の原始元 x
+        let p = |v: &[i64]| Manipulative::new(v.iter().map(|v| Field::new(*v, 3)).collect());
+        let one = ExtField::try_new(p(&[1]), p(&[2, 2, 1])).unwrap();
+        let a = one.root();
+        let msg = vec![a.clone(), one.clone(), a.pow(5), one.zero_like()];
+        check(8, 5, a.clone(), &msg, &[(1, a.pow(3)), (4, one.clone())]);
+    }
+    #[test]
+    fn too_many_errors_test() {
+        use crate::gf2m::Gf2m;
+        let one = Gf2m::try_new(1, 0b10011).unwrap();
+        let a = one.root();
+        // 2誤り訂正の符号に4つの誤り
+        let mut y = vec![one.zero_like(); 15];
+        for i in [0, 3, 5, 9] {
+            y[i] = one.clone();
+        }
+        assert_eq!(
+            euclid_decrypt_generic(15, 5, a.clone(), &y),
+            Err(DecodeError::TooManyErrors)
+        );
+        assert_eq!(
+            berlekamp_massey_decrypt_generic(15, 5, a.clone(), &y),
+            Err(DecodeError::TooManyErrors)
+        );
+        assert_eq!(
+            Code::new(15, 5, a).pgz_decrypt(&y),
+            Err(DecodeError::TooManyErrors)
+        );
+        assert_eq!(
+            DecodeError::TooManyErrors.to_string(),
+            "too many errors to correct"
+        );
+    }
+    #[test]
+    fn berlekamp_massey_test() {
+        let y = [1, -1, 1, 0, 3, 2, 0, 1];
+        let w = berlekamp_massey_decrypt(11, 8, 5, 2, &y).unwrap();
+        assert_eq!(w.error_positions, [3, 6]);
+        let w = berlekamp_massey_decrypt(5, 4, 3, 2, &[4, 0, 2, 1]).unwrap();
+        assert!(w.error_positions.is_empty());
+    }
+    fn pgz_decrypt(
+        p: u64,
+        n: usize,
+        d: usize,
+        a: i64,
+        y: &[i64],
+    ) -> Result<DecodeResult<Field>, DecodeError> {
+        let y: Vec<Field> = y.iter().map(|v| Field::new(*v, p)).collect();
+        Code::new(n, d, Field::new(a, p)).pgz_decrypt(&y)
+    }
+    #[test]
+    fn pgz_test() {
+        let w = pgz_decrypt(5, 4, 3, 2, &[1, 0, 2, 1]).unwrap();
+        assert_eq!(
+            w.codeword,
+            Manipulative::new([4, 0, 2, 1].iter().map(|v| Field::new(*v, 5)).collect())
+        );
+        let w = pgz_decrypt(11, 8, 5, 2, &[1, -1, 1, 0, 3, 2, 0, 1]).unwrap();
+        assert_eq!(w.error_positions, [3, 6]);
+        assert_eq!(w.error_values, [Field::new(3, 11), Field::new(6, 11)]);
+        let e = euclid_decrypt(11, 8, 5, 2, &[1, -1, 1, 0, 3, 2, 0, 1]).unwrap();
+        assert_eq!(&w.locator, &e.locator);
+        assert_eq!(&w.evaluator, &e.evaluator);
+    }
+    #[test]
+    fn erasures_test() {
+        use crate::gf2m::Gf2m;
+        let w = euclid_decrypt_erasures(11, 8, 5, 2, &[1, 10, 1, 8, 3, 2, 0, 1], &[6, 0, 6, 1]);
+        let w = w.unwrap();
+        assert_eq!(w.error_positions, [0, 1, 6]);
+        assert_eq!(
+            w.error_values,
+            [Field::new(0, 11), Field::new(0, 11), Field::new(6, 11)]
+        );
+
+        let one = Gf2m::try_new(1, 0x11d).unwrap();
+        let a = one.root();
+        let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+        for d in [2, 5, 17, 33] {
+            let msg: Vec<_> = (0..256 - d)
+                .map(|_| one.new_like(rng.next(256) as u32))
+                .collect();
+            let c = encrypt_generic(255, d, a.clone(), &msg);
+            for _ in 0..20 {
+                let s = rng.next(d as u64) as usize;
+                let e = (d - 1 - s) / 2;
+                let mut y = c.factors_padded(255);
+                let mut erasures = Vec::new();
+                for _ in 0..s {
+                    let j = rng.next(255) as usize;
+                    y[j] = one.new_like(rng.next(256) as u32);
+                    erasures.push(j);
+                }
+                for _ in 0..e {
+                    let j = rng.next(255) as usize;
+                    y[j] += one.new_like(rng.next(255) as u32 + 1);
+                }
+                let w = euclid_decrypt_erasures_generic(255, d, a.clone(), &y, &erasures).unwrap();
+                assert_eq!(w.codeword, c);
+                // 消失位置はすべて, 誤りは実際に値が変わった位置だけが返る
+                let mut expected = erasures.clone();
+                let padded = c.factors_padded(255);
+                expected.extend((0..255).filter(|&j| y[j] != padded[j]));
+                expected.sort_unstable();
+                expected.dedup();
+                assert_eq!(w.error_positions, expected);
+            }
+            // 消失が d - 1 個を超えると訂正できない
+            let erasures: Vec<usize> = (0..d).collect();
+            assert_eq!(
+                euclid_decrypt_erasures_generic(
+                    255,
+                    d,
+                    a.clone(),
+                    &c.factors_padded(255),
+                    &erasures
+                ),
+                Err(DecodeError::TooManyErrors)
+            );
+        }
+    }
+    #[test]
+    #[should_panic(expected = "受信語の長さ")]
+    fn too_long_test() {
+        let _ = euclid_decrypt(5, 4, 3, 2, &[1, 0, 2, 1, 3]);
+    }
+    #[test]
+    #[should_panic(expected = "受信語の長さ")]
+    fn berlekamp_massey_too_long_test() {
+        let _ = berlekamp_massey_decrypt(5, 4, 3, 2, &[1, 0, 2, 1, 3]);
+    }
+    #[test]
+    #[should_panic(expected = "受信語の長さ")]
+    fn pgz_too_long_test() {
+        let _ = pgz_decrypt(5, 4, 3, 2, &[1, 0, 2, 1, 3]);
+    }
+    #[test]
+    fn cross_check_test() {
+        use crate::gf2m::Gf2m;
+        let one = Gf2m::try_new(1, 0x11d).unwrap();
+        let a = one.root();
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+        for d in [2, 3, 8, 17, 33] {
+            let msg: Vec<_> = (0..256 - d)
+                .map(|_| one.new_like(rng.next(256) as u32))
+                .collect();
+            let c = encrypt_generic(255, d, a.clone(), &msg);
+            for _ in 0..20 {
+                let mut y = c.factors_padded(255);
+                for _ in 0..rng.next((d as u64 - 1) / 2 + 1) {
+                    y[rng.next(255) as usize] += one.new_like(rng.next(255) as u32 + 1);
+                }
+                let e = euclid_decrypt_generic(255, d, a.clone(), &y).unwrap();
+                let b = berlekamp_massey_decrypt_generic(255, d, a.clone(), &y).unwrap();
+                let p = Code::new(255, d, a.clone()).pgz_decrypt(&y).unwrap();
+                assert_eq!(e.codeword, c);
+                assert_eq!(b.codeword, c);
+                assert_eq!(p.codeword, c);
+                assert_eq!(&e.locator, &p.locator);
+                assert_eq!(e.error_positions, b.error_positions);
+                assert_eq!(e.error_values, b.error_values);
+                assert_eq!(&e.locator, &b.locator);
+                assert_eq!(&e.evaluator, &b.evaluator);
+            }
+        }
+        let f = |v: u64| Field::new(v as i64, 97);
+        let a = f(5); // 位数96
+        let msg: Vec<_> = (0..80).map(|_| f(rng.next(97))).collect();
+        let c = encrypt_generic(96, 17, a, &msg);
+        for _ in 0..20 {
+            let mut y = c.factors_padded(96);
+            for _ in 0..rng.next(9) {
+                y[rng.next(96) as usize] += f(rng.next(96) + 1);
+            }
+            let e = euclid_decrypt_generic(96, 17, a, &y).unwrap();
+            let b = berlekamp_massey_decrypt_generic(96, 17, a, &y).unwrap();
+            let p = Code::new(96, 17, a).pgz_decrypt(&y).unwrap();
+            assert_eq!(e.codeword, c);
+            assert_eq!(e.codeword, b.codeword);
+            assert_eq!(e.codeword, p.codeword);
+            assert_eq!(e.error_values, b.error_values);
+        }
+    }
 }
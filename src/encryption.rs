@@ -0,0 +1,182 @@
+use super::code::Code;
+use super::manipulative::Manipulative;
+use super::modulo::Field;
+use super::traits::FieldElement;
+
+/// 符号長 `n` の符号の生成多項式 g(x) = (x - a)(x - a^2)...(x - a^(d-1)) を求める.
+/// 零点を変えるときは `Code::generator_polynomial` を使う.
+/// # Panics
+/// `2 <= d <= n` でないとき, `a` が0のとき
+/// # Example
+/// ```
+/// # use finite_field::manipulative::Manipulative;
+/// # use finite_field::modulo::Field;
+/// # use finite_field::encryption::generator_polynomial;
+/// let g = generator_polynomial(4, 3, Field::new(2, 5)); // (x - 2)(x - 4)
+/// assert_eq!(
+///     g,
+///     Manipulative::new([3, 4, 1].iter().map(|v| Field::new(*v, 5)).collect())
+/// );
+/// ```
+pub fn generator_polynomial<T: FieldElement>(n: usize, d: usize, a: T) -> Manipulative<T> {
+    Code::new(n, d, a).generator_polynomial()
+}
+/// `euclid_decrypt` と同じ符号で, 情報 `msg` に生成多項式を掛けて符号化する.
+/// p: 体の標数, n: 符号長; C={f(a^i) (1<=i<=d-1) = 0}を線形符号とする.
+/// msg: 情報多項式のスライス(次数の低い順)
+/// # Panics
+/// `2 <= d <= n` でないとき, `msg` が空か `n - d + 1` 個より多いとき
+/// # Example
+/// ```
+/// # use finite_field::encryption::encrypt;
+/// # use finite_field::decryption::euclid_decrypt;
+/// let c = encrypt(11, 10, 5, 2, &[3, 1, 4, 1, 5, 9]);
+/// let mut y: Vec<i64> = c.factors_padded(10).iter().map(|v| v.value() as i64).collect();
+/// y[2] += 1;
+/// y[7] += 3;
+/// assert_eq!(euclid_decrypt(11, 10, 5, 2, &y).unwrap().codeword, c);
+/// ```
+pub fn encrypt(p: u64, n: usize, d: usize, a: i64, msg: &[i64]) -> Manipulative<Field> {
+    let msg: Vec<Field> = msg.iter().map(|v| Field::new(*v, p)).collect();
+    encrypt_generic(n, d, Field::new(a, p), &msg)
+}
+/// 任意の有限体上で, 情報 `msg` に生成多項式を掛けて符号化する.
+/// 符号語は次数が `n` 未満の多項式で, 長さ `n` の列は `factors_padded(n)` で得られる.
+/// # Panics
+/// `2 <= d <= n` でないとき, `msg` が空か `n - d + 1` 個より多いとき
+pub fn encrypt_generic<T: FieldElement>(n: usize, d: usize, a: T, msg: &[T]) -> Manipulative<T> {
+    Code::new(n, d, a).encrypt(msg)
+}
+/// `euclid_decrypt` と同じ符号で組織符号化する.
+/// 符号語の `x^(d-1)` 以上の係数に `msg` がそのまま現れる.
+/// # Panics
+/// `2 <= d <= n` でないとき, `msg` が空か `n - d + 1` 個より多いとき
+/// # Example
+/// ```
+/// # use finite_field::manipulative::Manipulative;
+/// # use finite_field::modulo::Field;
+/// # use finite_field::encryption::encrypt_systematic;
+/// let c = encrypt_systematic(5, 4, 3, 2, &[1, 2]);
+/// assert_eq!(
+///     c,
+///     Manipulative::new([4, 3, 1, 2].iter().map(|v| Field::new(*v, 5)).collect())
+/// );
+/// ```
+pub fn encrypt_systematic(p: u64, n: usize, d: usize, a: i64, msg: &[i64]) -> Manipulative<Field> {
+    let msg: Vec<Field> = msg.iter().map(|v| Field::new(*v, p)).collect();
+    encrypt_systematic_generic(n, d, Field::new(a, p), &msg)
+}
+/// 任意の有限体上で組織符号化する.
+/// c(x) = x^(d-1) m(x) - (x^(d-1) m(x) mod g(x))
+/// # Panics
+/// `2 <= d <= n` でないとき, `msg` が空か `n - d + 1` 個より多いとき
+pub fn encrypt_systematic_generic<T: FieldElement>(
+    n: usize,
+    d: usize,
+    a: T,
+    msg: &[T],
+) -> Manipulative<T> {
+    Code::new(n, d, a).encrypt_systematic(msg)
+}
+impl<T: FieldElement> Code<T> {
+    /// 情報 `msg` に生成多項式を掛けて符号化する. 符号語は次数が `n` 未満の多項式.
+    /// # Panics
+    /// `msg` が空か `n - d + 1` 個より多いとき
+    pub fn encrypt(&self, msg: &[T]) -> Manipulative<T> {
+        self.check(msg.len());
+        let g = self.generator_polynomial();
+        &g * &Manipulative::new(msg.to_vec())
+    }
+    /// 組織符号化する. c(x) = x^(d-1) m(x) - (x^(d-1) m(x) mod g(x))
+    /// # Panics
+    /// `msg` が空か `n - d + 1` 個より多いとき
+    pub fn encrypt_systematic(&self, msg: &[T]) -> Manipulative<T> {
+        self.check(msg.len());
+        let g = self.generator_polynomial();
+        let mut shifted = vec![self.root().zero_like(); self.distance() - 1];
+        shifted.extend_from_slice(msg);
+        let shifted = Manipulative::new(shifted);
+        let (_, r) = shifted.divide_by(&g).unwrap();
+        shifted - &r
+    }
+    fn check(&self, k: usize) {
+        if k == 0 || k > self.dimension() {
+            panic!("情報の長さが1以上n - d + 1以下でない")
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decryption::euclid_decrypt_generic;
+    use crate::fp::Fp;
+    use crate::gf2m::Gf2m;
+    use crate::traits::Ring;
+
+    #[test]
+    fn generator_test() {
+        let a = Fp::<11>::new(2);
+        let g = generator_polynomial(10, 5, a);
+        assert_eq!(g.deg(), Some(4));
+        let mut x = Fp::<11>::new(1);
+        for i in 1..=5 {
+            x *= a;
+            assert_eq!(g.assign(x).is_zero(), i < 5);
+        }
+    }
+    #[test]
+    fn encrypt_test() {
+        let c = encrypt(11, 10, 5, 2, &[3, 1, 4, 1, 5, 9]);
+        assert_eq!(c.deg(), Some(9));
+        let mut a = Field::new(1, 11);
+        for _ in 1..5 {
+            a *= 2;
+            assert!(c.assign(a).is_zero());
+        }
+        // 短い情報は生成多項式の定数倍
+        let c = encrypt(11, 10, 5, 2, &[3]);
+        assert_eq!(c.deg(), Some(4));
+        assert_eq!(c.factors_padded(10).len(), 10);
+    }
+    #[test]
+    fn systematic_test() {
+        let one = Gf2m::try_new(1, 0x11d).unwrap();
+        let a = one.root();
+        let msg: Vec<_> = (0..20).map(|i| a.pow(i * i)).collect();
+        let c = encrypt_systematic_generic(30, 11, a.clone(), &msg);
+        assert_eq!(&c.factors_padded(30)[10..], &msg[..]);
+        let mut x = one;
+        for _ in 1..11 {
+            x *= a.clone();
+            assert!(c.assign(x.clone()).is_zero());
+        }
+    }
+    #[test]
+    fn round_trip_test() {
+        let one = Gf2m::try_new(1, 0x11d).unwrap();
+        let a = one.root();
+        let msg: Vec<_> = (0..239).map(|i| one.new_like(i * 37 % 256)).collect();
+        for &systematic in &[false, true] {
+            let c = if systematic {
+                encrypt_systematic_generic(255, 17, a.clone(), &msg)
+            } else {
+                encrypt_generic(255, 17, a.clone(), &msg)
+            };
+            let mut y = c.factors_padded(255);
+            for i in 0..8 {
+                y[i * 31 + 4] += a.pow(i as u64 + 1);
+            }
+            assert_eq!(
+                euclid_decrypt_generic(255, 17, a.clone(), &y)
+                    .unwrap()
+                    .codeword,
+                c
+            );
+        }
+    }
+    #[test]
+    #[should_panic(expected = "情報の長さ")]
+    fn too_long_test() {
+        encrypt(11, 10, 5, 2, &[1; 7]);
+    }
+}
@@ -0,0 +1,370 @@
+use super::manipulative::Manipulative;
+use super::modulo::{is_prime, Field, FieldError};
+use super::traits::{EuclideanDomain, FieldElement, Ring};
+use super::zmod::factorize;
+use std::fmt;
+use std::ops;
+use std::sync::Arc;
+/// 拡大体 GF(p^k) の元.
+///
+/// GF(p) 上の `k` 次既約多項式 `m` を法とする `Manipulative<Field>` の剰余類で表す.
+/// 値は常に次数が `k` 未満の `m` での余りに正規化されている.
+/// # Example
+/// ```
+/// # use finite_field::ext_field::ExtField;
+/// # use finite_field::manipulative::Manipulative;
+/// # use finite_field::modulo::Field;
+/// let f = |v: &[i64]| Manipulative::new(v.iter().map(|v| Field::new(*v, 2)).collect());
+/// // GF(4) = GF(2)[x] / (x^2 + x + 1)
+/// let one = ExtField::try_new(f(&[1]), f(&[1, 1, 1])).unwrap();
+/// let a = one.root();
+/// assert_eq!(a.clone() * a.clone(), a.clone() + one.clone());
+/// assert_eq!(a.clone() * !a.clone(), one);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExtField {
+    v: Manipulative<Field>,
+    m: Arc<Manipulative<Field>>,
+}
+impl ExtField {
+    /// 係数の法 `p` が素数で, 法 `m` が既約であることを確かめてから, `v` の `m` での剰余類を作る.
+    pub fn try_new(v: Manipulative<Field>, m: Manipulative<Field>) -> Result<Self, FieldError> {
+        let p = m.modulus()?;
+        v.check_modulus(&m)?;
+        if !is_prime(p) {
+            return Err(FieldError::NotPrime(p));
+        }
+        let k = m.deg().unwrap_or(0);
+        if k < 1 {
+            return Err(FieldError::NotIrreducible);
+        }
+        // モニックにしておく
+        let lead = !m.factors()[k];
+        let m = Manipulative::new(m.factors().iter().map(|a| *a * lead).collect());
+        if !is_irreducible(&m) {
+            return Err(FieldError::NotIrreducible);
+        }
+        Ok(Self {
+            v: reduce(&v, &m),
+            m: Arc::new(m),
+        })
+    }
+    /// `self` と同じ体で `v` の剰余類を作る.
+    pub fn new_like(&self, v: Manipulative<Field>) -> Self {
+        Self {
+            v: reduce(&v, &self.m),
+            m: self.m.clone(),
+        }
+    }
+    /// `self` と同じ体で `GF(p)` の元 `x` を埋め込む.
+    pub fn from_field_like(&self, x: Field) -> Self {
+        self.new_like(Manipulative::new(vec![x]))
+    }
+    /// 法の多項式の根, すなわち `x` の剰余類
+    pub fn root(&self) -> Self {
+        let p = self.characteristic();
+        self.new_like(Manipulative::new(vec![Field::new(0, p), Field::new(1, p)]))
+    }
+    /// 剰余類の代表元(次数が `degree()` 未満)
+    pub fn value(&self) -> &Manipulative<Field> {
+        &self.v
+    }
+    /// 法の多項式(モニック)
+    pub fn modulus(&self) -> &Manipulative<Field> {
+        &self.m
+    }
+    /// 標数 `p`
+    pub fn characteristic(&self) -> u64 {
+        self.m.factors()[0].modulus()
+    }
+    /// 拡大次数 `k`
+    pub fn degree(&self) -> usize {
+        self.m.factors().len() - 1
+    }
+    pub fn pow(&self, exp: u64) -> Self {
+        Self {
+            v: pow_mod(&self.v, exp, &self.m),
+            m: self.m.clone(),
+        }
+    }
+    /// 逆元. 0のときは `Err`
+    pub fn checked_inv(&self) -> Result<Self, FieldError> {
+        match self.v.inverse_mod(&self.m) {
+            Some(v) => Ok(Self {
+                v,
+                m: self.m.clone(),
+            }),
+            None => Err(FieldError::NotInvertible),
+        }
+    }
+    fn check_modulus(&self, other: &ExtField) {
+        debug_assert!(
+            Arc::ptr_eq(&self.m, &other.m) || self.m == other.m,
+            "法が一致しない"
+        );
+    }
+}
+/// `a` を `m` で割った余り
+fn reduce(a: &Manipulative<Field>, m: &Manipulative<Field>) -> Manipulative<Field> {
+    let (_, r) = a.divide_by(m).unwrap();
+    r
+}
+fn pow_mod(a: &Manipulative<Field>, mut exp: u64, m: &Manipulative<Field>) -> Manipulative<Field> {
+    let mut base = a.clone();
+    let mut acc = reduce(
+        &Manipulative::new(vec![Field::new(1, m.factors()[0].modulus())]),
+        m,
+    );
+    while exp > 0 {
+        if exp & 1 == 1 {
+            acc = reduce(&(&acc * &base), m);
+        }
+        base = reduce(&(&base * &base), m);
+        exp >>= 1;
+    }
+    acc
+}
+/// モニックな `m` がGF(p)上既約かどうかをRabinの判定法で調べる.
+fn is_irreducible(m: &Manipulative<Field>) -> bool {
+    let p = m.factors()[0].modulus();
+    let k = m.factors().len() - 1;
+    let x = reduce(
+        &Manipulative::new(vec![Field::new(0, p), Field::new(1, p)]),
+        m,
+    );
+    // x^(p^j) mod m
+    let frobenius = |j: usize| (0..j).fold(x.clone(), |h, _| pow_mod(&h, p, m));
+    for (q, _) in factorize(k as u64) {
+        let h = frobenius(k / q as usize) - &x;
+        if m.gcd(&h).deg() != Some(0) {
+            return false;
+        }
+    }
+    (frobenius(k) - &x).is_zero()
+}
+impl Ring for ExtField {
+    fn zero_like(&self) -> Self {
+        let p = self.characteristic();
+        Self {
+            v: Manipulative::new(vec![Field::new(0, p)]),
+            m: self.m.clone(),
+        }
+    }
+    fn one_like(&self) -> Self {
+        self.from_field_like(Field::new(1, self.characteristic()))
+    }
+    fn is_zero(&self) -> bool {
+        self.v.is_zero()
+    }
+}
+impl EuclideanDomain for ExtField {
+    fn div_rem(&self, other: &Self) -> (Self, Self) {
+        (self.clone() / other.clone(), self.zero_like())
+    }
+}
+impl FieldElement for ExtField {
+    fn inv(&self) -> Option<Self> {
+        self.checked_inv().ok()
+    }
+}
+impl fmt::Display for ExtField {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.v)
+    }
+}
+impl ops::Neg for ExtField {
+    type Output = ExtField;
+    fn neg(self) -> Self {
+        let zero = self.zero_like();
+        zero - self
+    }
+}
+/// 逆元.
+/// # Panics
+/// 0のとき
+impl ops::Not for ExtField {
+    type Output = ExtField;
+    fn not(self) -> Self {
+        self.checked_inv().expect("逆元が存在しない")
+    }
+}
+impl ops::Add for ExtField {
+    type Output = ExtField;
+    fn add(mut self, other: ExtField) -> Self {
+        self += other;
+        self
+    }
+}
+impl ops::AddAssign for ExtField {
+    fn add_assign(&mut self, other: ExtField) {
+        self.check_modulus(&other);
+        self.v += &other.v;
+    }
+}
+impl ops::Sub for ExtField {
+    type Output = ExtField;
+    fn sub(mut self, other: ExtField) -> Self {
+        self -= other;
+        self
+    }
+}
+impl ops::SubAssign for ExtField {
+    fn sub_assign(&mut self, other: ExtField) {
+        self.check_modulus(&other);
+        self.v -= &other.v;
+    }
+}
+impl ops::Mul for ExtField {
+    type Output = ExtField;
+    fn mul(mut self, other: ExtField) -> Self {
+        self *= other;
+        self
+    }
+}
+impl ops::MulAssign for ExtField {
+    fn mul_assign(&mut self, other: ExtField) {
+        self.check_modulus(&other);
+        self.v = reduce(&(&self.v * &other.v), &self.m);
+    }
+}
+impl ops::Div for ExtField {
+    type Output = ExtField;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: ExtField) -> Self {
+        self * !other
+    }
+}
+impl ops::DivAssign for ExtField {
+    #[allow(clippy::suspicious_op_assign_impl)]
+    fn div_assign(&mut self, other: ExtField) {
+        *self *= !other;
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn poly(v: &[i64], p: u64) -> Manipulative<Field> {
+        Manipulative::new(v.iter().map(|v| Field::new(*v, p)).collect())
+    }
+    fn one(m: &[i64], p: u64) -> ExtField {
+        ExtField::try_new(poly(&[1], p), poly(m, p)).unwrap()
+    }
+    /// 全ての元を列挙する
+    fn elements(one: &ExtField) -> Vec<ExtField> {
+        let (p, k) = (one.characteristic(), one.degree());
+        (0..p.pow(k as u32))
+            .map(|mut i| {
+                let v = (0..k)
+                    .map(|_| {
+                        let d = i % p;
+                        i /= p;
+                        d as i64
+                    })
+                    .collect::<Vec<_>>();
+                one.new_like(poly(&v, p))
+            })
+            .collect()
+    }
+    #[test]
+    fn irreducible_test() {
+        assert_eq!(
+            ExtField::try_new(poly(&[1], 5), poly(&[1, 0, 1], 5)),
+            Err(FieldError::NotIrreducible)
+        );
+        assert_eq!(
+            ExtField::try_new(poly(&[1], 2), poly(&[1, 0, 0, 0, 1], 2)),
+            Err(FieldError::NotIrreducible)
+        );
+        assert_eq!(
+            ExtField::try_new(poly(&[1], 2), poly(&[1], 2)),
+            Err(FieldError::NotIrreducible)
+        );
+        assert_eq!(
+            ExtField::try_new(poly(&[1], 3), poly(&[1, 0, 1], 2)),
+            Err(FieldError::ModulusMismatch(3, 2))
+        );
+        // 係数の法が合成数
+        assert_eq!(
+            ExtField::try_new(poly(&[1], 9), poly(&[1, 0, 1], 9)),
+            Err(FieldError::NotPrime(9))
+        );
+        assert_eq!(
+            ExtField::try_new(poly(&[1], 6), poly(&[1, 1, 2], 6)),
+            Err(FieldError::NotPrime(6))
+        );
+        // (x^2 + x + 1)^2 はx^4+x^2+1で, GF(2)上で根を持たないが可約
+        assert_eq!(
+            ExtField::try_new(poly(&[1], 2), poly(&[1, 0, 1, 0, 1], 2)),
+            Err(FieldError::NotIrreducible)
+        );
+        assert!(ExtField::try_new(poly(&[1], 3), poly(&[1, 0, 1], 3)).is_ok());
+        // 最高次の係数が1でなくてもよい
+        let a = ExtField::try_new(poly(&[1], 3), poly(&[2, 0, 2], 3)).unwrap();
+        assert_eq!(a.modulus(), &poly(&[1, 0, 1], 3));
+    }
+    #[test]
+    fn field_test() {
+        // GF(4), GF(8), GF(9), GF(25)
+        for (m, p) in [
+            (&[1, 1, 1][..], 2),
+            (&[1, 1, 0, 1][..], 2),
+            (&[1, 0, 1][..], 3),
+            (&[2, 1, 1][..], 5),
+        ] {
+            let one = one(m, p);
+            let all = elements(&one);
+            let q = all.len();
+            for x in &all {
+                assert_eq!(x.clone() + -x.clone(), one.zero_like());
+                if !x.is_zero() {
+                    assert_eq!(x.clone() * !x.clone(), one);
+                    assert_eq!(x.pow(q as u64 - 1), one);
+                }
+                for y in &all {
+                    assert_eq!(x.clone() * y.clone(), y.clone() * x.clone());
+                    if !y.is_zero() {
+                        assert_eq!(x.clone() / y.clone() * y.clone(), *x);
+                    }
+                }
+            }
+            assert_eq!(
+                one.zero_like().checked_inv(),
+                Err(FieldError::NotInvertible)
+            );
+        }
+    }
+    #[test]
+    fn gf256_test() {
+        // 0x11d = x^8 + x^4 + x^3 + x^2 + 1 は原始多項式
+        let one = one(&[1, 0, 1, 1, 1, 0, 0, 0, 1], 2);
+        let a = one.root();
+        let mut x = one.clone();
+        for i in 1..=255 {
+            x *= a.clone();
+            assert_eq!(x == one, i == 255);
+        }
+        assert_eq!(a.pow(8), one.new_like(poly(&[1, 0, 1, 1, 1], 2)));
+        assert_eq!(a.clone().to_string(), "1 x + 0");
+    }
+    #[test]
+    fn manipulative_test() {
+        // GF(9) 上の (x - a)(x + a) = x^2 - a^2
+        let one = one(&[1, 0, 1], 3);
+        let a = one.root();
+        let f = Manipulative::new(vec![-a.clone(), one.clone()]);
+        let g = Manipulative::new(vec![a.clone(), one.clone()]);
+        let h = &f * &g;
+        assert_eq!(
+            h,
+            Manipulative::new(vec![one.clone(), one.zero_like(), one.clone()])
+        );
+        assert_eq!(h.assign(a.clone()), one.zero_like());
+        let (q, r) = h.divide_by(&f).unwrap();
+        assert_eq!(
+            q,
+            Manipulative::new(vec![a.clone(), one.clone(), one.zero_like()])
+        );
+        assert!(r.is_zero());
+    }
+}
@@ -0,0 +1,268 @@
+use super::modulo::{add_mod, inv_mod, is_prime, mul_mod, reduce, sub_mod, Field, FieldError};
+use super::traits::{EuclideanDomain, FieldElement, One, Ring, Zero};
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops;
+/// 法 `P` を型に持つ素体 GF(P) の元.
+///
+/// 値は常に `0..P` に正規化されている. 法の異なる元どうしの演算は型エラーになる.
+/// `P` が素数でないときは `new` がコンパイルエラーになる.
+/// # Example
+/// ```
+/// # use finite_field::fp::Fp;
+/// let x = Fp::<5>::new(2) / Fp::<5>::new(3);
+/// assert_eq!(x, Fp::<5>::new(4));
+/// assert_eq!(Fp::<5>::new(-1).value(), 4);
+/// ```
+/// ```compile_fail
+/// # use finite_field::fp::Fp;
+/// let x = Fp::<6>::new(1);
+/// ```
+/// `zero` と `default` も同じ.
+/// ```compile_fail
+/// # use finite_field::fp::Fp;
+/// let x = Fp::<4>::default();
+/// ```
+/// ```compile_fail
+/// # use finite_field::fp::Fp;
+/// # use finite_field::traits::Zero;
+/// let x = Fp::<4>::zero();
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Fp<const P: u64> {
+    v: u64,
+}
+impl<const P: u64> Fp<P> {
+    const PRIME: () = assert!(is_prime(P), "Pが素数でない");
+    pub fn new(value: i64) -> Self {
+        let () = Self::PRIME;
+        Self {
+            v: reduce(value, P),
+        }
+    }
+    /// `0..P` に正規化された値
+    pub fn value(self) -> u64 {
+        self.v
+    }
+    /// 法
+    pub const fn modulus() -> u64 {
+        P
+    }
+    /// 逆元. 0のときは `Err`
+    pub fn checked_inv(self) -> Result<Self, FieldError> {
+        match inv_mod(self.v, P) {
+            Some(v) => Ok(Self { v }),
+            None => Err(FieldError::NotInvertible),
+        }
+    }
+    /// 割り算. 0で割るときは `Err`
+    pub fn checked_div(self, other: Self) -> Result<Self, FieldError> {
+        Ok(self * other.checked_inv()?)
+    }
+}
+impl<const P: u64> Ring for Fp<P> {
+    fn zero_like(&self) -> Self {
+        Self::zero()
+    }
+    fn one_like(&self) -> Self {
+        Self::one()
+    }
+    fn is_zero(&self) -> bool {
+        self.v == 0
+    }
+}
+impl<const P: u64> EuclideanDomain for Fp<P> {
+    fn div_rem(&self, other: &Self) -> (Self, Self) {
+        (*self / *other, Self::zero())
+    }
+}
+impl<const P: u64> FieldElement for Fp<P> {
+    fn inv(&self) -> Option<Self> {
+        self.checked_inv().ok()
+    }
+}
+impl<const P: u64> Zero for Fp<P> {
+    fn zero() -> Self {
+        let () = Self::PRIME;
+        Self { v: 0 }
+    }
+}
+impl<const P: u64> Default for Fp<P> {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+impl<const P: u64> One for Fp<P> {
+    fn one() -> Self {
+        Self::new(1)
+    }
+}
+impl<const P: u64> From<Fp<P>> for Field {
+    fn from(x: Fp<P>) -> Field {
+        Field::from_reduced(x.v, P)
+    }
+}
+impl<const P: u64> TryFrom<Field> for Fp<P> {
+    type Error = FieldError;
+    fn try_from(x: Field) -> Result<Self, FieldError> {
+        if x.modulus() != P {
+            return Err(FieldError::ModulusMismatch(x.modulus(), P));
+        }
+        Ok(Self { v: x.value() })
+    }
+}
+impl<const P: u64> fmt::Display for Fp<P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.v)
+    }
+}
+impl<const P: u64> ops::Neg for Fp<P> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self {
+            v: sub_mod(0, self.v, P),
+        }
+    }
+}
+/// 逆元.
+/// # Panics
+/// 0のとき
+impl<const P: u64> ops::Not for Fp<P> {
+    type Output = Self;
+    fn not(self) -> Self {
+        self.checked_inv().expect("逆元が存在しない")
+    }
+}
+impl<const P: u64> ops::Add for Fp<P> {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Self {
+            v: add_mod(self.v, other.v, P),
+        }
+    }
+}
+impl<const P: u64> ops::Add<i64> for Fp<P> {
+    type Output = Self;
+    fn add(self, other: i64) -> Self {
+        self + Self::new(other)
+    }
+}
+impl<const P: u64> ops::AddAssign for Fp<P> {
+    fn add_assign(&mut self, other: Self) {
+        self.v = add_mod(self.v, other.v, P);
+    }
+}
+impl<const P: u64> ops::Sub for Fp<P> {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Self {
+            v: sub_mod(self.v, other.v, P),
+        }
+    }
+}
+impl<const P: u64> ops::SubAssign for Fp<P> {
+    fn sub_assign(&mut self, other: Self) {
+        self.v = sub_mod(self.v, other.v, P);
+    }
+}
+impl<const P: u64> ops::Mul for Fp<P> {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        Self {
+            v: mul_mod(self.v, other.v, P),
+        }
+    }
+}
+impl<const P: u64> ops::Mul<i64> for Fp<P> {
+    type Output = Self;
+    fn mul(self, other: i64) -> Self {
+        self * Self::new(other)
+    }
+}
+impl<const P: u64> ops::MulAssign for Fp<P> {
+    fn mul_assign(&mut self, other: Self) {
+        self.v = mul_mod(self.v, other.v, P);
+    }
+}
+impl<const P: u64> ops::MulAssign<i64> for Fp<P> {
+    fn mul_assign(&mut self, other: i64) {
+        *self *= Self::new(other);
+    }
+}
+impl<const P: u64> ops::Div for Fp<P> {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: Self) -> Self {
+        self * !other
+    }
+}
+impl<const P: u64> ops::DivAssign for Fp<P> {
+    #[allow(clippy::suspicious_op_assign_impl)]
+    fn div_assign(&mut self, other: Self) {
+        *self *= !other;
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manipulative::Manipulative;
+
+    type F5 = Fp<5>;
+    type F7 = Fp<7>;
+
+    #[test]
+    fn arith_test() {
+        assert_eq!(F5::new(2) + F5::new(4), F5::new(1));
+        assert_eq!(F5::new(2) - F5::new(4), F5::new(3));
+        assert_eq!(F5::new(2) * F5::new(4), F5::new(3));
+        assert_eq!(F5::new(2) / F5::new(3), F5::new(4));
+        assert_eq!(-F5::new(2), F5::new(3));
+        assert_eq!(F5::new(-1), F5::new(4));
+        assert_eq!(F5::new(3) * -1, F5::new(2));
+        let mut x = F7::new(3);
+        x += F7::new(5);
+        x -= F7::new(2);
+        x *= F7::new(4);
+        x /= F7::new(3);
+        assert_eq!(x, F7::new(1));
+        assert_eq!(F5::modulus(), 5);
+    }
+    #[test]
+    fn zero_one_test() {
+        assert_eq!(F5::zero(), F5::default());
+        assert!(F5::zero().is_zero());
+        assert!(!F5::one().is_zero());
+        assert_eq!(F5::one(), F5::new(6));
+        assert_eq!(F5::zero().checked_inv(), Err(FieldError::NotInvertible));
+        assert_eq!(!Fp::<2>::one(), Fp::<2>::one());
+    }
+    #[test]
+    fn large_test() {
+        type F = Fp<{ u64::MAX - 58 }>;
+        let m1 = F::new(-1);
+        assert_eq!(m1 * m1, F::one());
+        assert_eq!(!F::new(2) * F::new(2), F::one());
+    }
+    #[test]
+    fn conversion_test() {
+        assert_eq!(Field::from(F5::new(3)), Field::new(3, 5));
+        assert_eq!(F5::try_from(Field::new(8, 5)), Ok(F5::new(3)));
+        assert_eq!(
+            F5::try_from(Field::new(3, 7)),
+            Err(FieldError::ModulusMismatch(7, 5))
+        );
+    }
+    #[test]
+    fn manipulative_test() {
+        let f = |v: &[i64]| Manipulative::new(v.iter().map(|v| F5::new(*v)).collect());
+        let a = f(&[1, 2, 1]);
+        let b = f(&[1, 1]);
+        assert_eq!(&b * &b, a);
+        assert_eq!(a.divide_by(&b).unwrap(), (f(&[1, 1, 0]), f(&[0, 0, 0])));
+        let a = f(&[2, 3, 1]);
+        let b = f(&[0, 2]);
+        assert_eq!(a.divide_by(&b).unwrap(), (f(&[4, 3, 0]), f(&[2, 0, 0])));
+        assert_eq!(a.assign(F5::new(2)), F5::new(2));
+        assert_eq!(a.to_string(), "1 x^2 + 3 x + 2");
+    }
+}
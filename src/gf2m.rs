@@ -0,0 +1,392 @@
+use super::modulo::FieldError;
+use super::traits::{EuclideanDomain, FieldElement, Ring};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops;
+use std::sync::{Arc, Mutex, Weak};
+/// GF(2^m) の指数表と対数表. 原始多項式ごとに1つ作り, 同じ体の元で共有する.
+#[derive(Debug)]
+struct Tables {
+    poly: u32,
+    m: u32,
+    /// `exp[i] = a^i` (`0 <= i < 2 (2^m - 1)`)
+    exp: Vec<u16>,
+    /// `log[a^i] = i`
+    log: Vec<u16>,
+}
+impl Tables {
+    fn build(poly: u32) -> Result<Tables, FieldError> {
+        if !(2..1 << 17).contains(&poly) {
+            return Err(FieldError::NotPrimitive);
+        }
+        let m = 31 - poly.leading_zeros();
+        let order = (1usize << m) - 1;
+        let mut exp = vec![0; 2 * order];
+        let mut log = vec![0; order + 1];
+        let mut x = 1u32;
+        for i in 0..order {
+            if i > 0 && x == 1 {
+                // 位数が2^m - 1より小さい
+                return Err(FieldError::NotPrimitive);
+            }
+            exp[i] = x as u16;
+            exp[i + order] = x as u16;
+            log[x as usize] = i as u16;
+            x <<= 1;
+            if x >> m & 1 == 1 {
+                x ^= poly;
+            }
+        }
+        if x != 1 {
+            return Err(FieldError::NotPrimitive);
+        }
+        Ok(Tables { poly, m, exp, log })
+    }
+    fn order(&self) -> usize {
+        (1 << self.m) - 1
+    }
+    /// `poly` の表. 使われている表があれば共有し, なければ作る.
+    fn get(poly: u32) -> Result<Arc<Tables>, FieldError> {
+        // 元がすべて捨てられた表は解放されるように弱参照で持つ
+        static CACHE: Mutex<BTreeMap<u32, Weak<Tables>>> = Mutex::new(BTreeMap::new());
+        let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(t) = cache.get(&poly).and_then(Weak::upgrade) {
+            return Ok(t);
+        }
+        let t = Arc::new(Tables::build(poly)?);
+        cache.retain(|_, t| t.strong_count() > 0);
+        cache.insert(poly, Arc::downgrade(&t));
+        Ok(t)
+    }
+}
+/// 二元拡大体 GF(2^m) の元 (`m <= 16`).
+///
+/// ビット `i` が `x^i` の係数を表す. 足し算は排他的論理和,
+/// 掛け算は原始多項式から作った指数表・対数表で行う.
+/// 表は原始多項式ごとに作り, 同じ体の元で共有する.
+/// # Example
+/// ```
+/// # use finite_field::gf2m::Gf2m;
+/// let a = Gf2m::try_new(0x53, 0x11d).unwrap();
+/// let b = a.new_like(0xca);
+/// assert_eq!((a.clone() + b.clone()).value(), 0x53 ^ 0xca);
+/// assert_eq!(a.clone() * b.clone() / b, a);
+/// assert_eq!(a.root().pow(8).value(), 0x1d);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Gf2m {
+    v: u16,
+    t: Arc<Tables>,
+}
+impl Gf2m {
+    /// 原始多項式 `poly` (例えば `0x11d`) で定まる GF(2^m) の元を作る.
+    /// `v` は多項式とみなして `poly` で割った余りにする.
+    /// 表は同じ `poly` の元が残っていれば共有するので, 2回目以降は作り直さない.
+    pub fn try_new(v: u32, poly: u32) -> Result<Self, FieldError> {
+        let t = Tables::get(poly)?;
+        Ok(Self {
+            v: reduce(v, &t),
+            t,
+        })
+    }
+    /// `self` と同じ体の元を作る
+    pub fn new_like(&self, v: u32) -> Self {
+        Self {
+            v: reduce(v, &self.t),
+            t: self.t.clone(),
+        }
+    }
+    /// 原始多項式の根, すなわち `x`. 乗法群の生成元になる.
+    pub fn root(&self) -> Self {
+        self.new_like(2)
+    }
+    pub fn value(&self) -> u16 {
+        self.v
+    }
+    /// 原始多項式
+    pub fn poly(&self) -> u32 {
+        self.t.poly
+    }
+    /// 拡大次数 `m`
+    pub fn degree(&self) -> u32 {
+        self.t.m
+    }
+    /// `root()` を底とする離散対数. 0のときは `None`
+    pub fn log(&self) -> Option<usize> {
+        if self.v == 0 {
+            return None;
+        }
+        Some(self.t.log[self.v as usize] as usize)
+    }
+    pub fn pow(&self, exp: u64) -> Self {
+        match self.log() {
+            Some(l) => Self {
+                v: self.t.exp
+                    [(l as u64 * (exp % self.t.order() as u64) % self.t.order() as u64) as usize],
+                t: self.t.clone(),
+            },
+            None if exp == 0 => self.one_like(),
+            None => self.clone(),
+        }
+    }
+    /// 逆元. 0のときは `Err`
+    pub fn checked_inv(&self) -> Result<Self, FieldError> {
+        match self.log() {
+            Some(l) => Ok(Self {
+                v: self.t.exp[self.t.order() - l],
+                t: self.t.clone(),
+            }),
+            None => Err(FieldError::NotInvertible),
+        }
+    }
+    /// 同じ原始多項式で作った体か
+    fn same_field(&self, other: &Gf2m) -> bool {
+        Arc::ptr_eq(&self.t, &other.t) || self.t.poly == other.t.poly
+    }
+    fn check_poly(&self, other: &Gf2m) {
+        debug_assert!(self.same_field(other), "法が一致しない");
+    }
+}
+fn reduce(mut v: u32, t: &Tables) -> u16 {
+    for i in (t.m..32).rev() {
+        if v >> i & 1 == 1 {
+            v ^= t.poly << (i - t.m);
+        }
+    }
+    v as u16
+}
+impl PartialEq for Gf2m {
+    fn eq(&self, other: &Gf2m) -> bool {
+        self.v == other.v && self.same_field(other)
+    }
+}
+impl Eq for Gf2m {}
+impl Hash for Gf2m {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.v.hash(state);
+        self.t.poly.hash(state);
+    }
+}
+impl Ring for Gf2m {
+    fn zero_like(&self) -> Self {
+        Self {
+            v: 0,
+            t: self.t.clone(),
+        }
+    }
+    fn one_like(&self) -> Self {
+        Self {
+            v: 1,
+            t: self.t.clone(),
+        }
+    }
+    fn is_zero(&self) -> bool {
+        self.v == 0
+    }
+    fn mul_usize(&self, k: usize) -> Self {
+        if k & 1 == 1 {
+            self.clone()
+        } else {
+            self.zero_like()
+        }
+    }
+}
+impl EuclideanDomain for Gf2m {
+    fn div_rem(&self, other: &Self) -> (Self, Self) {
+        (self.clone() / other.clone(), self.zero_like())
+    }
+}
+impl FieldElement for Gf2m {
+    fn inv(&self) -> Option<Self> {
+        self.checked_inv().ok()
+    }
+}
+impl fmt::Display for Gf2m {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.v)
+    }
+}
+impl ops::Neg for Gf2m {
+    type Output = Gf2m;
+    fn neg(self) -> Self {
+        self
+    }
+}
+/// 逆元.
+/// # Panics
+/// 0のとき
+impl ops::Not for Gf2m {
+    type Output = Gf2m;
+    fn not(self) -> Self {
+        self.checked_inv().expect("逆元が存在しない")
+    }
+}
+impl ops::Add for Gf2m {
+    type Output = Gf2m;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(mut self, other: Gf2m) -> Self {
+        self += other;
+        self
+    }
+}
+impl ops::AddAssign for Gf2m {
+    #[allow(clippy::suspicious_op_assign_impl)]
+    fn add_assign(&mut self, other: Gf2m) {
+        self.check_poly(&other);
+        self.v ^= other.v;
+    }
+}
+impl ops::Sub for Gf2m {
+    type Output = Gf2m;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn sub(self, other: Gf2m) -> Self {
+        self + other
+    }
+}
+impl ops::SubAssign for Gf2m {
+    #[allow(clippy::suspicious_op_assign_impl)]
+    fn sub_assign(&mut self, other: Gf2m) {
+        *self += other;
+    }
+}
+impl ops::Mul for Gf2m {
+    type Output = Gf2m;
+    fn mul(mut self, other: Gf2m) -> Self {
+        self *= other;
+        self
+    }
+}
+impl ops::MulAssign for Gf2m {
+    fn mul_assign(&mut self, other: Gf2m) {
+        self.check_poly(&other);
+        if self.v == 0 || other.v == 0 {
+            self.v = 0;
+            return;
+        }
+        let (l, r) = (self.t.log[self.v as usize], self.t.log[other.v as usize]);
+        self.v = self.t.exp[l as usize + r as usize];
+    }
+}
+impl ops::Div for Gf2m {
+    type Output = Gf2m;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: Gf2m) -> Self {
+        self * !other
+    }
+}
+impl ops::DivAssign for Gf2m {
+    #[allow(clippy::suspicious_op_assign_impl)]
+    fn div_assign(&mut self, other: Gf2m) {
+        *self *= !other;
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manipulative::Manipulative;
+
+    /// 繰り上がりのない掛け算をしてから `poly` で割る
+    fn clmul(a: u32, b: u32, poly: u32) -> u16 {
+        let mut r = 0u32;
+        for i in 0..16 {
+            if b >> i & 1 == 1 {
+                r ^= a << i;
+            }
+        }
+        let m = 31 - poly.leading_zeros();
+        for i in (m..32).rev() {
+            if r >> i & 1 == 1 {
+                r ^= poly << (i - m);
+            }
+        }
+        r as u16
+    }
+    #[test]
+    fn primitive_test() {
+        assert!(Gf2m::try_new(1, 0x11d).is_ok());
+        assert!(Gf2m::try_new(1, 0x187).is_ok());
+        assert!(Gf2m::try_new(1, 0b11).is_ok());
+        // AESの多項式は既約だが原始的でない
+        assert_eq!(Gf2m::try_new(1, 0x11b), Err(FieldError::NotPrimitive));
+        assert_eq!(Gf2m::try_new(1, 0x100), Err(FieldError::NotPrimitive));
+        assert_eq!(Gf2m::try_new(1, 1), Err(FieldError::NotPrimitive));
+        assert_eq!(Gf2m::try_new(1, 1 << 17), Err(FieldError::NotPrimitive));
+        let a = Gf2m::try_new(0x1ff, 0x11d).unwrap();
+        assert_eq!(a.value(), 0x1ff ^ 0x11d);
+        assert_eq!(a.degree(), 8);
+    }
+    #[test]
+    fn gf256_test() {
+        for &poly in &[0x11d, 0x187] {
+            let one = Gf2m::try_new(1, poly).unwrap();
+            for a in 0..256 {
+                let x = one.new_like(a);
+                assert_eq!(x.clone() + x.clone(), one.zero_like());
+                assert_eq!(-x.clone(), x);
+                if a != 0 {
+                    assert_eq!(x.clone() * !x.clone(), one);
+                    assert_eq!(x.pow(255), one);
+                }
+                for b in 0..256 {
+                    let y = one.new_like(b);
+                    assert_eq!((x.clone() * y.clone()).value(), clmul(a, b, poly));
+                    assert_eq!((x.clone() - y).value(), (a ^ b) as u16);
+                }
+            }
+            assert_eq!(
+                one.zero_like().checked_inv(),
+                Err(FieldError::NotInvertible)
+            );
+            assert_eq!(one.root().log(), Some(1));
+            assert_eq!(one.zero_like().pow(0), one);
+        }
+    }
+    #[test]
+    fn gf65536_test() {
+        let one = Gf2m::try_new(1, 0x1100b).unwrap();
+        for a in 1..1 << 16 {
+            let x = one.new_like(a);
+            assert_eq!(x.clone() / x, one);
+        }
+        let (x, y) = (one.new_like(0xbeef), one.new_like(0x1234));
+        assert_eq!((x * y).value(), clmul(0xbeef, 0x1234, 0x1100b));
+    }
+    #[test]
+    fn cache_test() {
+        let a = Gf2m::try_new(1, 0x11d).unwrap();
+        let b = Gf2m::try_new(2, 0x11d).unwrap();
+        assert!(Arc::ptr_eq(&a.t, &b.t));
+        assert!(!Arc::ptr_eq(&a.t, &Gf2m::try_new(1, 0x187).unwrap().t));
+    }
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "法が一致しない")]
+    fn mismatch_test() {
+        let _ = Gf2m::try_new(1, 0x11d).unwrap() + Gf2m::try_new(1, 0x187).unwrap();
+    }
+    #[test]
+    fn manipulative_test() {
+        let one = Gf2m::try_new(1, 0x11d).unwrap();
+        let a = one.root();
+        // (x - a)(x - a^2)
+        let f = Manipulative::new(vec![a.clone(), one.clone()]);
+        let g = Manipulative::new(vec![a.pow(2), one.clone()]);
+        let h = &f * &g;
+        assert_eq!(
+            h,
+            Manipulative::new(vec![a.pow(3), a.clone() + a.pow(2), one.clone()])
+        );
+        assert!(h.assign(a.pow(2)).is_zero());
+        let (q, r) = h.divide_by(&g).unwrap();
+        assert_eq!(
+            q,
+            Manipulative::new(vec![a.clone(), one.clone(), one.zero_like()])
+        );
+        assert!(r.is_zero());
+        assert_eq!(
+            h.diff(),
+            Manipulative::new(vec![a.clone() + a.pow(2), one.zero_like()])
+        );
+    }
+}
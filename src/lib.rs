@@ -0,0 +1,57 @@
+//! 有限体上の計算とユークリッド復号を行うライブラリ.
+//!
+//! # Example
+//! ```
+//! use finite_field::prelude::*;
+//!
+//! let x = Field::new(2, 5) / Field::new(3, 5);
+//! assert_eq!(x, Field::new(4, 5));
+//!
+//! let w = euclid_decrypt(5, 4, 3, 2, &[1, 0, 2, 1]).unwrap();
+//! assert_eq!(
+//!     w.codeword,
+//!     Manipulative::new([4, 0, 2, 1].iter().map(|v| Field::new(*v, 5)).collect())
+//! );
+//! ```
+pub mod code;
+mod convolution;
+pub mod decryption;
+pub mod encryption;
+pub mod ext_field;
+pub mod fp;
+pub mod gf2m;
+pub mod list_decryption;
+pub mod manipulative;
+pub mod modulo;
+pub mod montgomery;
+pub mod rational;
+#[cfg(test)]
+pub(crate) mod test_util;
+pub mod traits;
+pub mod zmod;
+
+pub use prelude::*;
+
+/// よく使う型と関数をまとめて読み込むためのモジュール.
+pub mod prelude {
+    pub use crate::code::Code;
+    pub use crate::decryption::{
+        berlekamp_massey_decrypt, berlekamp_massey_decrypt_generic, euclid_decrypt,
+        euclid_decrypt_erasures, euclid_decrypt_erasures_generic, euclid_decrypt_generic,
+        DecodeError, DecodeResult,
+    };
+    pub use crate::encryption::{
+        encrypt, encrypt_generic, encrypt_systematic, encrypt_systematic_generic,
+        generator_polynomial,
+    };
+    pub use crate::ext_field::ExtField;
+    pub use crate::fp::Fp;
+    pub use crate::gf2m::Gf2m;
+    pub use crate::list_decryption::{guruswami_sudan_decrypt, sudan_decrypt};
+    pub use crate::manipulative::{Manipulative, PolyError};
+    pub use crate::modulo::{Field, FieldError};
+    pub use crate::montgomery::MontFp;
+    pub use crate::rational::Rational;
+    pub use crate::traits::{EuclideanDomain, FieldElement, One, Ring, Zero};
+    pub use crate::zmod::Zmod;
+}
@@ -0,0 +1,632 @@
+use super::code::{pow, Code};
+use super::decryption::DecodeError;
+use super::manipulative::Manipulative;
+use super::modulo::{add_mod, inv_mod, mul_mod, sub_mod, Field};
+use super::traits::Ring;
+
+/// 二変数多項式 Q(x, y) = Σ Q_j(x) y^j. `j` 番目が y^j の係数.
+type Bivariate = Vec<Manipulative<Field>>;
+
+/// Sudanのアルゴリズムでリスト復号する. 符号と引数は `euclid_decrypt` と同じ.
+/// 受信語からの距離が `Code::list_decoding_radius(1)` 以下の符号語をすべて返す.
+/// # Panics
+/// `2 <= d < n` でないとき, `y` の長さがnより大きいとき, aの位数がn未満のとき
+/// # Example
+/// ```
+/// # use finite_field::encryption::encrypt;
+/// # use finite_field::list_decryption::sudan_decrypt;
+/// // GF(11) 上の (10, 3) 符号は4誤りまで訂正できないが, リスト復号はできる
+/// let c = encrypt(11, 10, 8, 2, &[3, 1, 4]);
+/// let mut y: Vec<i64> = c.factors_padded(10).iter().map(|v| v.value() as i64).collect();
+/// for i in [0, 3, 5, 9] {
+///     y[i] += 1;
+/// }
+/// assert!(sudan_decrypt(11, 10, 8, 2, &y).contains(&c));
+/// ```
+pub fn sudan_decrypt(p: u64, n: usize, d: usize, a: i64, y: &[i64]) -> Vec<Manipulative<Field>> {
+    guruswami_sudan_decrypt(p, n, d, a, y, 1)
+}
+/// 重複度 `m` のGuruswami-Sudanのアルゴリズムでリスト復号する. 符号と引数は `euclid_decrypt` と同じ.
+/// 受信語からの距離が `Code::list_decoding_radius(m)` 以下の符号語をすべて返す.
+/// この半径は `m` を大きくするとJohnson半径に近づくが, 届くとは限らない.
+/// Johnson半径まで復号するときは `Code::johnson_decrypt` を使う.
+/// # Panics
+/// `2 <= d < n` でないとき, `m` が0のとき, `y` の長さがnより大きいとき, aの位数がn未満のとき
+pub fn guruswami_sudan_decrypt(
+    p: u64,
+    n: usize,
+    d: usize,
+    a: i64,
+    y: &[i64],
+    m: usize,
+) -> Vec<Manipulative<Field>> {
+    let y: Vec<Field> = y.iter().map(|v| Field::new(*v, p)).collect();
+    Code::new(n, d, Field::new(a, p)).guruswami_sudan_decrypt(&y, m)
+}
+impl Code<Field> {
+    /// 重複度 `m` のGuruswami-Sudanのアルゴリズムで必ず見つかる符号語の, 受信語からの距離の上限.
+    ///
+    /// 重み (1, k-1) の次数が D 以下の単項式の数が n m(m+1)/2 を超える最小の D について
+    /// n - floor(D / m) - 1 になる. m を大きくするとJohnson半径に近づく.
+    /// # Panics
+    /// `d = n` のとき, `m` が0のとき
+    pub fn list_decoding_radius(&self, m: usize) -> usize {
+        let (radius, _) = self.interpolation_degree(m);
+        radius
+    }
+    /// Johnson半径. 距離が n - sqrt(n(k-1)) 未満の最大の整数で, 十分大きい重複度で到達できる.
+    /// # Panics
+    /// `d = n` のとき
+    pub fn johnson_radius(&self) -> usize {
+        let (n, k) = (self.length(), self.dimension());
+        if k < 2 {
+            panic!("情報の長さが2未満")
+        }
+        let mut t = 0;
+        while t * t <= n * (k - 1) {
+            t += 1;
+        }
+        n - t
+    }
+    /// Sudanのアルゴリズムでリスト復号する. 重複度1の `guruswami_sudan_decrypt`.
+    /// # Panics
+    /// `d = n` のとき, `y` の長さがnより大きいとき, aの位数がn未満のとき
+    pub fn sudan_decrypt(&self, y: &[Field]) -> Vec<Manipulative<Field>> {
+        self.guruswami_sudan_decrypt(y, 1)
+    }
+    /// 重複度 `m` のGuruswami-Sudanのアルゴリズムでリスト復号する.
+    /// 受信語からの距離が `list_decoding_radius(m)` 以下の符号語を, 距離の近い順に返す.
+    /// Johnson半径まで復号するときは `johnson_decrypt` を使う.
+    ///
+    /// 補間はKötterのアルゴリズムで行う.
+    /// # Panics
+    /// `d = n` のとき, `m` が0のとき, `y` の長さがnより大きいとき, aの位数がn未満のとき
+    pub fn guruswami_sudan_decrypt(&self, y: &[Field], m: usize) -> Vec<Manipulative<Field>> {
+        let (radius, deg) = self.interpolation_degree(m);
+        self.list_decrypt_with(y, m, deg, radius)
+    }
+    /// 受信語からの距離がJohnson半径以下の符号語をすべて, 距離の近い順に返す.
+    /// `list_decrypt(y, johnson_radius())` と同じ.
+    /// # Panics
+    /// `d = n` のとき, `y` の長さがnより大きいとき, aの位数がn未満のとき
+    pub fn johnson_decrypt(&self, y: &[Field]) -> Result<Vec<Manipulative<Field>>, DecodeError> {
+        self.list_decrypt(y, self.johnson_radius())
+    }
+    /// 受信語からの距離が `radius` 以下の符号語をすべて返す.
+    /// `radius` に届く最小の重複度でのGuruswami-Sudanのアルゴリズムと, k 個の位置の組を
+    /// すべて試す方法のうち, 見積もりの手間が小さい方を使う.
+    ///
+    /// `radius` が `johnson_radius()` より大きいときは `DecodeError::RadiusTooLarge`
+    /// # Panics
+    /// `d = n` のとき, `y` の長さがnより大きいとき, aの位数がn未満のとき
+    pub fn list_decrypt(
+        &self,
+        y: &[Field],
+        radius: usize,
+    ) -> Result<Vec<Manipulative<Field>>, DecodeError> {
+        if radius > self.johnson_radius() {
+            return Err(DecodeError::RadiusTooLarge);
+        }
+        let mut m = 1;
+        while self.list_decoding_radius(m) < radius {
+            m += 1;
+        }
+        let (n, k) = (self.length(), self.dimension());
+        let (_, deg) = self.interpolation_degree(m);
+        // 組を試す手間 C(n, k) n k と, Kötterの補間の手間 (l + 1) C^2 (C は条件の数) を比べる
+        let subsets = (0..k).fold(1.0, |acc, i| acc * (n - i) as f64 / (i + 1) as f64);
+        let constraints = (n * m * (m + 1) / 2) as f64;
+        let interpolation = (deg / (k - 1) + 1) as f64 * constraints * constraints;
+        if subsets * (n * k) as f64 <= interpolation {
+            Ok(self.subset_decrypt(y, radius))
+        } else {
+            Ok(self.list_decrypt_with(y, m, deg, radius))
+        }
+    }
+    /// 重複度 `m` で補間するときの (復号半径, 補間多項式の重み付き次数)
+    fn interpolation_degree(&self, m: usize) -> (usize, usize) {
+        let (n, k) = (self.length(), self.dimension());
+        if k < 2 {
+            panic!("情報の長さが2未満")
+        }
+        if m == 0 {
+            panic!("重複度が0")
+        }
+        let constraints = n * m * (m + 1) / 2;
+        let mut deg = 0;
+        while monomial_count(deg, k - 1) <= constraints {
+            deg += 1;
+        }
+        (n.saturating_sub(deg / m + 1), deg)
+    }
+    fn list_decrypt_with(
+        &self,
+        y: &[Field],
+        m: usize,
+        deg: usize,
+        radius: usize,
+    ) -> Vec<Manipulative<Field>> {
+        let k = self.dimension();
+        let (points, multipliers, values) = self.received_values(y);
+        let q = interpolate(&points, &values, m, deg / (k - 1), k - 1);
+        let mut candidates = Vec::new();
+        roth_ruckenstein(q, k, &mut Vec::new(), &mut candidates);
+        let candidates = candidates.into_iter().map(Manipulative::new);
+        self.select(y, &points, &multipliers, candidates, radius)
+    }
+    /// k 個の位置の組をすべて試して, 一致する位置から f を補間する.
+    /// `radius <= n - k` なら距離 `radius` 以下の符号語はどれかの組で見つかる.
+    fn subset_decrypt(&self, y: &[Field], radius: usize) -> Vec<Manipulative<Field>> {
+        let (n, k) = (self.length(), self.dimension());
+        let (points, multipliers, values) = self.received_values(y);
+        let one = Manipulative::new(vec![points[0].one_like()]);
+        let mut subset: Vec<usize> = (0..k).collect();
+        let mut candidates = Vec::new();
+        loop {
+            // Lagrange補間
+            let mut f = Manipulative::new(vec![points[0].zero_like()]);
+            for &j in &subset {
+                let mut basis = one.clone();
+                let mut den = points[0].one_like();
+                for &l in subset.iter().filter(|&&l| l != j) {
+                    basis = &basis * &Manipulative::new(vec![-points[l], points[0].one_like()]);
+                    den *= points[j] - points[l];
+                }
+                f += &(&basis * &Manipulative::new(vec![values[j] / den]));
+            }
+            candidates.push(f);
+
+            let Some(i) = (0..k).rev().find(|&i| subset[i] < n - k + i) else {
+                break;
+            };
+            subset[i] += 1;
+            for l in i + 1..k {
+                subset[l] = subset[l - 1] + 1;
+            }
+        }
+        self.select(y, &points, &multipliers, candidates.into_iter(), radius)
+    }
+    /// (評価点 a^j, 列の重み v_j, y_j / v_j)
+    fn received_values(&self, y: &[Field]) -> (Vec<Field>, Vec<Field>, Vec<Field>) {
+        let (n, a) = (self.length(), self.root());
+        let y = self.received(y).factors_padded(n);
+        // 符号語は c_j = v_j f(a^j) (deg f < k) と書ける
+        let points: Vec<Field> = (0..n).map(|j| pow(a, j)).collect();
+        let multipliers = self.column_multipliers(&points);
+        let values: Vec<Field> = (0..n).map(|j| y[j] / multipliers[j]).collect();
+        (points, multipliers, values)
+    }
+    /// 候補 f のうち符号語が距離 `radius` 以下のものを, 距離の近い順に返す
+    fn select(
+        &self,
+        y: &[Field],
+        points: &[Field],
+        multipliers: &[Field],
+        candidates: impl Iterator<Item = Manipulative<Field>>,
+        radius: usize,
+    ) -> Vec<Manipulative<Field>> {
+        let n = self.length();
+        let zero = points[0].zero_like();
+        let mut result: Vec<(usize, Manipulative<Field>)> = Vec::new();
+        for f in candidates {
+            let c: Vec<Field> = (0..n)
+                .map(|j| multipliers[j] * f.assign(points[j]))
+                .collect();
+            let dist = (0..n)
+                .filter(|&j| c[j] != *y.get(j).unwrap_or(&zero))
+                .count();
+            let c = Manipulative::new(c);
+            if dist <= radius && result.iter().all(|(_, r)| *r != c) {
+                result.push((dist, c));
+            }
+        }
+        result.sort_by(|(l, f), (r, g)| l.cmp(r).then_with(|| f.factors().cmp(g.factors())));
+        result.into_iter().map(|(_, c)| c).collect()
+    }
+    /// 列の重み v_j = 1 / (a_j^b Π_(l != j) (a_j - a_l))
+    fn column_multipliers(&self, points: &[Field]) -> Vec<Field> {
+        let b = self.first_root();
+        points
+            .iter()
+            .enumerate()
+            .map(|(j, x)| {
+                let mut prod = pow(x, b);
+                for (l, z) in points.iter().enumerate() {
+                    if l != j {
+                        prod *= *x - *z;
+                    }
+                }
+                prod.checked_inv().expect("aの位数がn未満")
+            })
+            .collect()
+    }
+}
+/// 重み (1, w) の次数が `deg` 以下の単項式 x^i y^j の数
+fn monomial_count(deg: usize, w: usize) -> usize {
+    (0..=deg / w).map(|j| deg - w * j + 1).sum()
+}
+/// Kötterのアルゴリズムで扱う多項式 g_i.
+struct Candidate {
+    /// `poly[j]` は y^j の係数. 剰余を取らずに足し込むので `0..p` に収まるとは限らない
+    poly: Vec<Vec<u64>>,
+    /// 今の点でのHasse微分を条件の順に並べたもの
+    local: Vec<u64>,
+    /// (重み付き次数, 先頭の項の y の次数)
+    lead: (usize, usize),
+    /// 剰余を取らずに足し込んだ回数
+    pending: u64,
+}
+impl Candidate {
+    fn reduce(&mut self, p: u64) {
+        for f in self.poly.iter_mut() {
+            for v in f.iter_mut() {
+                *v %= p;
+            }
+            while f.last() == Some(&0) {
+                f.pop();
+            }
+        }
+        for v in self.local.iter_mut() {
+            *v %= p;
+        }
+        self.pending = 0;
+    }
+}
+/// 各点 (x_j, y_j) で重複度 `m` 以上の零点を持ち, y の次数が `l` 以下の Q(x, y) != 0 のうち,
+/// 重み (1, w) の次数が最小のものをKötterのアルゴリズムで求める.
+///
+/// 先頭の項が y^i の多項式 g_i (i <= l) を保ち, 条件「Hasse微分 D_(r,s) Q(x_j, y_j) = 0」を1つずつ課す.
+/// 条件を満たさない g_i のうち次数が最小のもので他を消し, それ自身には (x - x_j) を掛ける.
+/// 1点の条件を課す間は, Hasse微分も g_i と同じように更新して持つ.
+fn interpolate(xs: &[Field], ys: &[Field], m: usize, l: usize, w: usize) -> Bivariate {
+    let p = xs[0].modulus();
+    // p < 2^32 なら積が64ビットに収まるので, `limit` 回までは剰余を取らずに足し込める
+    let lazy = p >> 32 == 0;
+    let limit = if lazy {
+        (u64::MAX - p) / ((p - 1) * (p - 1)).max(1)
+    } else {
+        0
+    };
+    let mut g: Vec<Candidate> = (0..=l)
+        .map(|i| {
+            let mut poly = vec![Vec::new(); l + 1];
+            poly[i] = vec![1];
+            Candidate {
+                poly,
+                local: Vec::new(),
+                lead: (w * i, i),
+                pending: 0,
+            }
+        })
+        .collect();
+    // 1点での条件 (r, s) を s ごとにまとめて並べる. (r - 1, s) は (r, s) より先に課す
+    let blocks: Vec<usize> = (0..m).map(|s| m - s).collect();
+    let mut delta = vec![0; l + 1];
+    for (x, y) in xs.iter().zip(ys.iter()) {
+        for q in g.iter_mut() {
+            q.reduce(p);
+        }
+        let len = g
+            .iter()
+            .flat_map(|q| q.poly.iter())
+            .map(|f| f.len())
+            .max()
+            .unwrap();
+        let binom = binomials(len.max(l + 1), m, p);
+        let x_weights = weights(x.value(), len, m, &binom, p);
+        let y_weights = weights(y.value(), l + 1, m, &binom, p);
+        for q in g.iter_mut() {
+            // taylor[r][j] = D_r g_i[j](x_j)
+            let taylor: Vec<Vec<u64>> = x_weights
+                .iter()
+                .map(|x_w| q.poly.iter().map(|f| dot(f, x_w, p)).collect())
+                .collect();
+            q.local.clear();
+            for (y_w, &block) in y_weights.iter().zip(blocks.iter()) {
+                q.local
+                    .extend(taylor[..block].iter().map(|t| dot(t, y_w, p)));
+            }
+        }
+        for c in 0..g[0].local.len() {
+            for (d, q) in delta.iter_mut().zip(g.iter()) {
+                *d = q.local[c] % p;
+            }
+            let pivot = match (0..=l)
+                .filter(|&i| delta[i] != 0)
+                .min_by_key(|&i| g[i].lead)
+            {
+                Some(pivot) => pivot,
+                None => continue,
+            };
+            let inv = inv_mod(delta[pivot], p).unwrap();
+            g[pivot].reduce(p);
+            let pivot_poly = std::mem::take(&mut g[pivot].poly);
+            let pivot_local = std::mem::take(&mut g[pivot].local);
+            for (q, &d) in g.iter_mut().zip(delta.iter()) {
+                if d == 0 || q.local.is_empty() {
+                    continue;
+                }
+                if lazy && q.pending == limit {
+                    q.reduce(p);
+                }
+                let coefficient = p - mul_mod(d, inv, p);
+                // c より前の条件はどの g_i も満たしている
+                add_scaled(&mut q.local[c..], &pivot_local[c..], coefficient, p, lazy);
+                for (f, h) in q.poly.iter_mut().zip(pivot_poly.iter()) {
+                    if f.len() < h.len() {
+                        f.resize(h.len(), 0);
+                    }
+                    add_scaled(f, h, coefficient, p, lazy);
+                }
+                if lazy {
+                    q.pending += 1;
+                }
+            }
+            // (x - x_j) を掛ける. Hasse微分は D_(r,s) から D_(r+1,s) に移る
+            let q = &mut g[pivot];
+            q.poly = pivot_poly
+                .into_iter()
+                .map(|f| {
+                    let mut h = vec![0; f.len() + 1];
+                    for (k, v) in f.into_iter().enumerate() {
+                        h[k] = sub_mod(h[k], mul_mod(v, x.value(), p), p);
+                        h[k + 1] = v;
+                    }
+                    h
+                })
+                .collect();
+            let mut rest = &pivot_local[..];
+            for &block in &blocks {
+                q.local.push(0);
+                q.local.extend_from_slice(&rest[..block - 1]);
+                rest = &rest[block..];
+            }
+            q.lead.0 += 1;
+        }
+    }
+    let i = (0..=l).min_by_key(|&i| g[i].lead).unwrap();
+    let mut q = g.swap_remove(i);
+    q.reduce(p);
+    let len = q.poly.iter().rposition(|f| !f.is_empty()).unwrap() + 1;
+    q.poly
+        .into_iter()
+        .take(len)
+        .map(|f| {
+            let f: Vec<Field> = f.into_iter().map(|v| Field::new(v as i64, p)).collect();
+            Manipulative::new(if f.is_empty() {
+                vec![Field::new(0, p)]
+            } else {
+                f
+            })
+        })
+        .collect()
+}
+/// `binom[a][r]` = C(a, r) mod p (a < len, r < m)
+fn binomials(len: usize, m: usize, p: u64) -> Vec<Vec<u64>> {
+    let mut binom = vec![vec![0; m]; len];
+    for a in 0..len {
+        binom[a][0] = 1 % p;
+        for r in 1..m.min(a + 1) {
+            binom[a][r] = add_mod(binom[a - 1][r - 1], binom[a - 1][r], p);
+        }
+    }
+    binom
+}
+/// `weights[r][a]` = C(a, r) x^(a-r) mod p (r < m, a < len). 多項式との内積が r 階のHasse微分になる
+fn weights(x: u64, len: usize, m: usize, binom: &[Vec<u64>], p: u64) -> Vec<Vec<u64>> {
+    let mut x_pow = Vec::with_capacity(len);
+    let mut acc = 1 % p;
+    for _ in 0..len {
+        x_pow.push(acc);
+        acc = mul_mod(acc, x, p);
+    }
+    (0..m)
+        .map(|r| {
+            (0..len)
+                .map(|a| {
+                    if a < r {
+                        0
+                    } else {
+                        mul_mod(binom[a][r], x_pow[a - r], p)
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+/// Σ f_k w_k mod p
+fn dot(f: &[u64], w: &[u64], p: u64) -> u64 {
+    if p >> 32 == 0 {
+        // 積が64ビットに収まるので, まとめて割る
+        let sum: u128 = f.iter().zip(w.iter()).map(|(a, b)| (a * b) as u128).sum();
+        (sum % p as u128) as u64
+    } else {
+        f.iter()
+            .zip(w.iter())
+            .fold(0, |acc, (a, b)| add_mod(acc, mul_mod(*a, *b, p), p))
+    }
+}
+/// f += c h. `lazy` のときは剰余を取らない
+fn add_scaled(f: &mut [u64], h: &[u64], c: u64, p: u64, lazy: bool) {
+    if lazy {
+        for (a, &b) in f.iter_mut().zip(h.iter()) {
+            // どちらも32ビットに収まるので, 32ビット同士の積として計算させる
+            *a += (c as u32 as u64) * (b as u32 as u64);
+        }
+    } else {
+        for (a, b) in f.iter_mut().zip(h.iter()) {
+            *a = add_mod(*a, mul_mod(c, *b, p), p);
+        }
+    }
+}
+/// Roth-Ruckensteinの方法で Q(x, f(x)) = 0 となる次数 `k` 未満の f の候補を `out` に加える.
+/// `prefix` は決まった f の下位の係数.
+fn roth_ruckenstein(q: Bivariate, k: usize, prefix: &mut Vec<Field>, out: &mut Vec<Vec<Field>>) {
+    // Q を割り切る x の最大のべきで割る
+    let shift = q
+        .iter()
+        .filter_map(|q_j| q_j.factors().iter().position(|v| !v.is_zero()))
+        .min();
+    let shift = match shift {
+        Some(shift) => shift,
+        // Q = 0 にはならない
+        None => return,
+    };
+    if prefix.len() == k {
+        out.push(prefix.clone());
+        return;
+    }
+    let q: Bivariate = q
+        .into_iter()
+        .map(|q_j| {
+            let zero = q_j.factors()[0].zero_like();
+            Manipulative::new(
+                q_j.factors()
+                    .get(shift..)
+                    .filter(|f| !f.is_empty())
+                    .map_or(vec![zero], |f| f.to_vec()),
+            )
+        })
+        .collect();
+    // Q(0, y) の根が f の次の係数の候補
+    let q0 = Manipulative::new(q.iter().map(|q_j| q_j.factors()[0]).collect());
+    for gamma in q0.roots() {
+        prefix.push(gamma);
+        roth_ruckenstein(substitute(&q, gamma), k, prefix, out);
+        prefix.pop();
+    }
+}
+/// Q(x, γ + x y)
+fn substitute(q: &Bivariate, gamma: Field) -> Bivariate {
+    let p = gamma.modulus();
+    let gamma = Manipulative::new(vec![gamma]);
+    let x = Manipulative::new(vec![Field::new(0, p), Field::new(1, p)]);
+    // ホーナー法で R = R (γ + x y) + Q_j
+    let mut r: Bivariate = vec![q[q.len() - 1].clone()];
+    for q_j in q.iter().rev().skip(1) {
+        let mut next = Vec::with_capacity(r.len() + 1);
+        next.push(&r[0] * &gamma + q_j);
+        for j in 1..r.len() {
+            next.push(&r[j] * &gamma + &(&r[j - 1] * &x));
+        }
+        next.push(&r[r.len() - 1] * &x);
+        r = next;
+    }
+    r
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::Rng;
+
+    fn distance(c: &Manipulative<Field>, y: &[Field]) -> usize {
+        c.factors_padded(y.len())
+            .iter()
+            .zip(y.iter())
+            .filter(|(l, r)| l != r)
+            .count()
+    }
+    #[test]
+    fn radius_test() {
+        let f = |v: i64| Field::new(v, 17);
+        // (16, 4) 符号. 一意復号の半径は6
+        let code = Code::new(16, 13, f(3));
+        assert_eq!(code.list_decoding_radius(1), 7);
+        assert_eq!(code.list_decoding_radius(2), 8);
+        assert_eq!(code.johnson_radius(), 9);
+        let code = Code::new(10, 8, Field::new(2, 11));
+        assert_eq!(code.list_decoding_radius(1), 4);
+        assert_eq!(code.list_decoding_radius(3), 5);
+        assert_eq!(code.johnson_radius(), 5);
+    }
+    #[test]
+    fn sudan_test() {
+        let f = |v: u64| Field::new(v as i64, 17);
+        let mut rng = Rng(0x853c_49e6_748f_ea9b);
+        for &b in &[0, 1, 3] {
+            let code = Code::with_first_root(16, 13, f(3), b);
+            for m in 1..=2 {
+                let radius = code.list_decoding_radius(m);
+                for _ in 0..3 {
+                    let msg: Vec<_> = (0..4).map(|_| f(rng.next(17))).collect();
+                    let c = code.encrypt(&msg);
+                    let mut y = c.factors_padded(16);
+                    let mut errors = 0;
+                    while errors < radius {
+                        let j = rng.next(16) as usize;
+                        if y[j] == c.factors_padded(16)[j] {
+                            y[j] += f(rng.next(16) + 1);
+                            errors += 1;
+                        }
+                    }
+                    let list = code.guruswami_sudan_decrypt(&y, m);
+                    assert!(list.contains(&c));
+                    for w in &list {
+                        assert!(distance(w, &y) <= radius);
+                        for z in code.zeros() {
+                            assert!(w.assign(z).is_zero());
+                        }
+                    }
+                }
+            }
+        }
+    }
+    #[test]
+    fn johnson_test() {
+        // (16, 4) 符号のJohnson半径9には重複度28が要る
+        let f = |v: u64| Field::new(v as i64, 17);
+        let code = Code::new(16, 13, f(3));
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+        let msg: Vec<_> = (0..4).map(|_| f(rng.next(17))).collect();
+        let c = code.encrypt(&msg);
+        let mut y = c.factors_padded(16);
+        for v in y.iter_mut().take(9) {
+            *v += f(rng.next(16) + 1);
+        }
+        let list = code.johnson_decrypt(&y).unwrap();
+        assert!(list.contains(&c));
+        for w in &list {
+            assert!(distance(w, &y) <= 9);
+        }
+        // 半径8までならGuruswami-Sudanの結果と一致する
+        let near: Vec<_> = list.into_iter().filter(|w| distance(w, &y) <= 8).collect();
+        assert_eq!(code.guruswami_sudan_decrypt(&y, 3), near);
+    }
+    #[test]
+    #[should_panic(expected = "受信語の長さ")]
+    fn too_long_test() {
+        let f = |v: u64| Field::new(v as i64, 11);
+        let code = Code::new(10, 8, f(2));
+        let _ = code.johnson_decrypt(&[f(0); 11]);
+    }
+    #[test]
+    fn exhaustive_test() {
+        // GF(11) 上の (10, 3) 符号の全符号語と比べる
+        let f = |v: u64| Field::new(v as i64, 11);
+        let code = Code::new(10, 8, f(2));
+        let mut codewords = Vec::new();
+        for v in 0..11 * 11 * 11 {
+            codewords.push(code.encrypt(&[f(v % 11), f(v / 11 % 11), f(v / 121)]));
+        }
+        let mut rng = Rng(0xda94_2042_e4dd_58b5);
+        for _ in 0..10 {
+            let c = &codewords[rng.next(codewords.len() as u64) as usize];
+            let mut y = c.factors_padded(10);
+            for _ in 0..5 {
+                y[rng.next(10) as usize] = f(rng.next(11));
+            }
+            let mut expected: Vec<_> = codewords
+                .iter()
+                .filter(|w| distance(w, &y) <= 5)
+                .cloned()
+                .collect();
+            expected.sort_by_key(|w| (distance(w, &y), w.factors().to_vec()));
+            assert_eq!(code.list_decrypt(&y, 5), Ok(expected.clone()));
+            assert_eq!(code.johnson_decrypt(&y), Ok(expected.clone()));
+            assert_eq!(code.guruswami_sudan_decrypt(&y, 3), expected);
+        }
+        // Johnson半径を超える半径は指定できない
+        let y = codewords[0].factors_padded(10);
+        assert_eq!(code.list_decrypt(&y, 6), Err(DecodeError::RadiusTooLarge));
+    }
+}
@@ -1,23 +1,18 @@
-mod decryption;
-mod manipulative;
-mod modulo;
-use decryption::euclid_decrypt;
-use manipulative::Manipulative;
-use modulo::Field;
+use finite_field::prelude::*;
+
 fn main() {
     // 整式の割り算
     let a = Manipulative::new(vec![1, 2, 1]);
     let b = Manipulative::new(vec![1, 1]);
     assert_eq!(
         a.divide_by(&b).unwrap(),
-        (
-            Manipulative::new(vec![1, 1, 0]),
-            Manipulative::new(vec![0, 0, 0])
-        )
+        (Manipulative::new(vec![1, 1]), Manipulative::new(vec![0]))
     );
 
     // 過去問の問題のユークリッド復号
-    let w = euclid_decrypt(11, 7, 5, 2, &[1, -1, 1, 0, 3, 2, 0, 1]);
+    let w = euclid_decrypt(11, 8, 5, 2, &[1, -1, 1, 0, 3, 2, 0, 1])
+        .unwrap()
+        .codeword;
     println!("{}", w);
     assert_eq!(
         w,
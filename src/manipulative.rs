@@ -1,185 +1,475 @@
-use super::modulo::Field;
+use super::convolution::multiply;
+use super::modulo::{Field, FieldError};
+use super::traits::{EuclideanDomain, FieldElement, Ring};
+use super::zmod::Zmod;
+use std::any::Any;
+use std::error;
 use std::fmt;
-use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
-#[derive(Clone, Debug, PartialEq)]
+use std::ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign};
+/// 多項式の演算で起こるエラー.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PolyError {
+    /// 0多項式で割った
+    DivisionByZero,
+    /// 割る式の最高次の係数で割り切れない (環の係数のとき)
+    NonInvertibleLeadingCoefficient,
+    /// 係数の法が一致しない
+    ModulusMismatch(u64, u64),
+}
+impl fmt::Display for PolyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PolyError::DivisionByZero => write!(f, "division by zero polynomial"),
+            PolyError::NonInvertibleLeadingCoefficient => {
+                write!(f, "leading coefficient of divisor is not invertible")
+            }
+            PolyError::ModulusMismatch(l, r) => write!(f, "modulus mismatch: {} and {}", l, r),
+        }
+    }
+}
+impl error::Error for PolyError {}
+impl From<PolyError> for FieldError {
+    /// 法の不一致はそのまま, 割り算のエラーは逆元が存在しないことにする
+    fn from(e: PolyError) -> FieldError {
+        match e {
+            PolyError::ModulusMismatch(l, r) => FieldError::ModulusMismatch(l, r),
+            PolyError::DivisionByZero | PolyError::NonInvertibleLeadingCoefficient => {
+                FieldError::NotInvertible
+            }
+        }
+    }
+}
+/// 多項式. 係数は次数の低い順に持つ.
+///
+/// 最高次の係数が0にならないように常に正規化されていて, 0多項式は `[0]` で表す.
+/// そのため `==` は多項式として比べる.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Manipulative<T> {
     factors: Vec<T>,
 }
-impl<T> Manipulative<T>
-where
-    T: std::fmt::Debug
-        + Copy
-        + AddAssign
-        + SubAssign
-        + Neg<Output = T>
-        + Add<Output = T>
-        + Sub<Output = T>
-        + Mul<Output = T>
-        + Div<Output = T>
-        + PartialEq,
-{
-    pub fn new(factors: Vec<T>) -> Self {
-        if factors.len() == 0 {
+impl<T: Ring> Manipulative<T> {
+    /// 係数(次数の低い順)から作る. 上位の0は取り除く.
+    /// # Panics
+    /// 係数が空のとき
+    /// # Example
+    /// ```
+    /// # use finite_field::manipulative::Manipulative;
+    /// assert_eq!(Manipulative::new(vec![1, 0]), Manipulative::new(vec![1]));
+    /// assert_eq!(Manipulative::new(vec![0, 0]).factors(), [0]);
+    /// ```
+    pub fn new(mut factors: Vec<T>) -> Self {
+        if factors.is_empty() {
             panic!("係数が空");
         }
-        Self { factors: factors }
+        trim(&mut factors);
+        Self { factors }
+    }
+    /// 係数のスライス(次数の低い順). 最高次の係数は0でない(0多項式を除く).
+    pub fn factors(&self) -> &[T] {
+        &self.factors
     }
+    /// 係数を `len` 個になるまで0で埋めて返す. 符号語を長さ `n` の列として扱うときに使う.
+    pub fn factors_padded(&self, len: usize) -> Vec<T> {
+        let mut factors = self.factors.clone();
+        if factors.len() < len {
+            factors.resize(len, factors[0].zero_like());
+        }
+        factors
+    }
+    /// 0多項式かどうか
+    pub fn is_zero(&self) -> bool {
+        self.factors.len() == 1 && self.factors[0].is_zero()
+    }
+    pub fn assign(&self, x: T) -> T {
+        let mut result = x.zero_like();
+        for a in self.factors.iter().rev() {
+            result *= x.clone();
+            result += a.clone();
+        }
+        result
+    }
+    /// 次数. 0多項式は `None`
+    pub fn deg(&self) -> Option<usize> {
+        if self.is_zero() {
+            None
+        } else {
+            Some(self.factors.len() - 1)
+        }
+    }
+    pub fn diff(&self) -> Manipulative<T> {
+        if self.factors.len() == 1 {
+            return Manipulative::new(vec![self.factors[0].zero_like()]);
+        }
+        let mut new_factors = Vec::with_capacity(self.factors.len() - 1);
+        for (i, a) in self.factors.iter().enumerate() {
+            if i != 0 {
+                new_factors.push(a.mul_usize(i));
+            }
+        }
+        Manipulative::new(new_factors)
+    }
+    /// 係数の法 (`runtime_modulus`) がすべて一致するか確かめる
+    pub(crate) fn check_modulus(&self, other: &Manipulative<T>) -> Result<(), PolyError> {
+        let n = runtime_modulus(&self.factors[0]);
+        match self
+            .factors
+            .iter()
+            .chain(other.factors.iter())
+            .map(runtime_modulus)
+            .find(|m| *m != n)
+        {
+            Some(m) => Err(PolyError::ModulusMismatch(n.unwrap_or(0), m.unwrap_or(0))),
+            None => Ok(()),
+        }
+    }
+}
+/// 型でなく値が持つ法. 値ごとに法を持つ `Field` と `Zmod` だけが `Some` を返す.
+fn runtime_modulus<T: Ring>(a: &T) -> Option<u64> {
+    let a: &dyn Any = a;
+    match a.downcast_ref::<Field>() {
+        Some(a) => Some(a.modulus()),
+        None => a.downcast_ref::<Zmod>().map(|a| a.n),
+    }
+}
+/// 上位の0を取り除く. 0多項式は `[0]` にする.
+fn trim<T: Ring>(factors: &mut Vec<T>) {
+    while factors.len() > 1 && factors[factors.len() - 1].is_zero() {
+        factors.pop();
+    }
+}
+/// `divide_by` でNewton法を使う商と割る式の長さの下限
+const NEWTON_THRESHOLD: usize = 64;
+/// 係数列の積を `x^k` で割った余り
+fn mul_truncated<T: Ring>(lhs: &[T], rhs: &[T], k: usize) -> Vec<T> {
+    let mut result = multiply(&lhs[..lhs.len().min(k)], &rhs[..rhs.len().min(k)]);
+    result.truncate(k);
+    result
+}
+/// 定数項の逆元が `c` である `f` の, `x^k` を法とする逆元をNewton法で求める.
+/// g ← g(2 - fg) mod x^(2l)
+fn inverse_series<T: Ring>(f: &[T], c: T, k: usize) -> Vec<T> {
+    let two = c.one_like().mul_usize(2);
+    let mut g = vec![c];
+    while g.len() < k {
+        let l = (2 * g.len()).min(k);
+        let mut h: Vec<T> = mul_truncated(f, &g, l).into_iter().map(|v| -v).collect();
+        h[0] += two.clone();
+        g = mul_truncated(&g, &h, l);
+    }
+    g
 }
-impl<T> Manipulative<T>
-where
-    T: std::fmt::Debug
-        + Copy
-        + AddAssign
-        + SubAssign
-        + Neg<Output = T>
-        + Add<Output = T>
-        + Sub<Output = T>
-        + Mul<Output = T>
-        + MulAssign
-        + Div<Output = T>
-        + Default
-        + PartialEq,
-{
+impl<T: EuclideanDomain> Manipulative<T> {
+    /// 商と余り. 長いときは割る式の逆順の冪級数の逆元をNewton法で求めて掛ける.
+    ///
+    /// 0多項式で割ると `DivisionByZero`, 係数の法が一致しないと `ModulusMismatch`,
+    /// 係数が体でなく最高次の係数で割り切れない項が出ると `NonInvertibleLeadingCoefficient` を返す.
     pub fn divide_by(
         &self,
         other: &Manipulative<T>,
-    ) -> Result<(Manipulative<T>, Manipulative<T>), &'static str> {
-        let mut man_r = self.clone();
-        let mut q = Vec::with_capacity(self.factors.len());
-        q.resize_with(self.factors.len(), Default::default);
-        let (j, d) = (|| {
-            // otherの先頭要素を返す
-            for (j, d) in other.factors.iter().enumerate().rev() {
-                if *d != Default::default() {
-                    return Ok((j, *d));
-                }
+    ) -> Result<(Manipulative<T>, Manipulative<T>), PolyError> {
+        self.check_modulus(other)?;
+        let zero = self.factors[0].zero_like();
+        // otherの先頭要素
+        let m = other.deg().ok_or(PolyError::DivisionByZero)?;
+        let n = match self.deg() {
+            Some(n) if n >= m => n,
+            _ => return Ok((Manipulative::new(vec![zero]), self.clone())),
+        };
+        let d = other.factors[m].clone();
+        let k = n - m + 1;
+        if k.min(m + 1) >= NEWTON_THRESHOLD {
+            let one = d.one_like();
+            let (c, rem) = one.div_rem(&d);
+            if rem.is_zero() && c.clone() * d.clone() == one {
+                // rev(q) = rev(self) / rev(other) mod x^k
+                let rev_self: Vec<T> = self.factors.iter().rev().take(k).cloned().collect();
+                let rev_other: Vec<T> = other.factors.iter().rev().take(k).cloned().collect();
+                let inv = inverse_series(&rev_other, c, k);
+                let mut q = mul_truncated(&rev_self, &inv, k);
+                q.resize(k, zero);
+                q.reverse();
+                let q = Manipulative::new(q);
+                let r = self.clone() - &(&q * other);
+                return Ok((q, r));
             }
-            Err("devided by zero")
-        })()?;
-        'outer: loop {
-            let mut it = man_r.factors.iter().enumerate().rev();
-            let mut l;
-            let mut i;
-            while {
-                let ll = it.next();
-                match ll {
-                    Some((i_, l_)) => {
-                        if i_ < j {
-                            break 'outer;
-                        }
-                        l = *l_;
-                        i = i_;
-                        l == Default::default() // 0だったら繰り返す
-                    }
-                    None => break 'outer,
-                }
-            } {}
-            let a = l / d;
-            unsafe {
-                *q.get_unchecked_mut(i - j) = a;
+        }
+        // 筆算
+        let mut r = self.factors.clone();
+        let mut q = vec![zero; k];
+        for i in (m..=n).rev() {
+            if r[i].is_zero() {
+                continue;
+            }
+            let (a, rem) = r[i].div_rem(&d);
+            if !rem.is_zero() {
+                return Err(PolyError::NonInvertibleLeadingCoefficient);
+            }
+            for (l, o) in r[i - m..=i].iter_mut().zip(other.factors.iter()) {
+                *l -= a.clone() * o.clone();
             }
-            let mut o = other.clone();
-            let mut v = vec![Default::default(); i - j];
-            v.push(a);
-            o *= &Manipulative::new(v);
-            man_r -= &o;
+            q[i - m] = a;
         }
-        Ok((Manipulative::new(q), man_r))
+        Ok((Manipulative::new(q), Manipulative::new(r)))
     }
-    pub fn assign(&self, x: T) -> T {
-        let mut result = Default::default();
-        for a in self.factors.iter().rev() {
-            result *= x;
-            result += *a;
+}
+/// `xgcd` で半GCDを使う次数の下限
+const HALF_GCD_THRESHOLD: usize = 64;
+impl<T: FieldElement> Manipulative<T> {
+    /// 最高次の係数を1にする. 0多項式はそのまま.
+    pub fn monic(&self) -> Manipulative<T> {
+        let lead = &self.factors[self.factors.len() - 1];
+        match lead.inv() {
+            Some(c) => self * &Manipulative::new(vec![c]),
+            None => self.clone(),
         }
-        result
     }
-}
-impl Manipulative<Field> {
-    pub fn divide_by(
+    /// モニックな最大公約多項式. 両方0のときは0.
+    /// # Panics
+    /// 係数の法が一致しないとき
+    /// # Example
+    /// ```
+    /// # use finite_field::manipulative::Manipulative;
+    /// # use finite_field::modulo::Field;
+    /// let f = |v: &[i64]| Manipulative::new(v.iter().map(|v| Field::new(*v, 7)).collect());
+    /// // (x + 1)(x + 2) と 3(x + 1)(x + 3)
+    /// assert_eq!(f(&[2, 3, 1]).gcd(&f(&[9, 12, 3])), f(&[1, 1]));
+    /// ```
+    pub fn gcd(&self, other: &Manipulative<T>) -> Manipulative<T> {
+        self.xgcd(other).0
+    }
+    /// 拡張ユークリッドの互除法. `s self + t other = g` となる `(g, s, t)` を返す.
+    ///
+    /// `g` はモニックな最大公約多項式で, `deg s < deg other - deg g`, `deg t < deg self - deg g`.
+    /// 次数が大きいときは半GCDで剰余列を飛ばす.
+    /// # Panics
+    /// 係数の法が一致しないとき
+    pub fn xgcd(
         &self,
-        other: &Manipulative<Field>,
-    ) -> Result<(Manipulative<Field>, Manipulative<Field>), &'static str> {
-        let mut man_r = self.clone();
-        let mut q = Vec::with_capacity(self.factors.len());
-        let default = Field::new(0, unsafe { self.factors.get_unchecked(0).n });
-        q.resize(self.factors.len(), default);
-        let (j, d) = (|| {
-            // otherの先頭要素を返す
-            for (j, d) in other.factors.iter().enumerate().rev() {
-                if *d != default {
-                    return Ok((j, *d));
+        other: &Manipulative<T>,
+    ) -> (Manipulative<T>, Manipulative<T>, Manipulative<T>) {
+        self.check_modulus(other).expect("法が一致しない");
+        let mut m = Matrix::identity(&self.factors[0]);
+        let (mut r0, mut r1) = (self.clone(), other.clone());
+        while !r1.is_zero() {
+            if r0.deg() > r1.deg() && r0.factors.len() > HALF_GCD_THRESHOLD {
+                let h = half_gcd(&r0, &r1);
+                (r0, r1) = h.apply(&r0, &r1);
+                m = h.mul(&m);
+                if r1.is_zero() {
+                    break;
                 }
             }
-            Err("devide by zero")
-        })()?;
-        'outer: loop {
-            let mut it = man_r.factors.iter().enumerate().rev();
-            let mut l;
-            let mut i;
-            while {
-                let ll = it.next();
-                match ll {
-                    Some((i_, l_)) => {
-                        if i_ < j {
-                            break 'outer;
-                        }
-                        l = *l_;
-                        i = i_;
-                        l == default // 0だったら繰り返す
-                    }
-                    None => break 'outer,
-                }
-            } {}
-            let a = l / d;
-            unsafe {
-                *q.get_unchecked_mut(i - j) = a;
+            let (q, r) = r0.divide_by(&r1).unwrap();
+            (r0, r1) = (r1, r);
+            m = Matrix::quotient(&q).mul(&m);
+        }
+        let [s, t, _, _] = m.0;
+        match r0.factors[r0.factors.len() - 1].inv() {
+            Some(c) => {
+                let c = Manipulative::new(vec![c]);
+                (&r0 * &c, &s * &c, &t * &c)
             }
-            let mut o = other.clone();
-            let mut v = vec![default; i - j];
-            v.push(a);
-            o *= &Manipulative::new(v);
-            man_r -= &o;
+            None => (r0, s, t),
         }
-        Ok((Manipulative::new(q), man_r))
     }
-    pub fn assign(&self, x: Field) -> Field {
-        let mut result = Field::new(0, x.n);
-        for a in self.factors.iter().rev() {
-            result *= x;
-            result += *a;
+    /// モニックな最小公倍多項式. どちらかが0のときは0.
+    /// # Panics
+    /// 係数の法が一致しないとき
+    pub fn lcm(&self, other: &Manipulative<T>) -> Manipulative<T> {
+        self.check_modulus(other).expect("法が一致しない");
+        if self.is_zero() || other.is_zero() {
+            return Manipulative::new(vec![self.factors[0].zero_like()]);
         }
-        result
+        let (q, _) = self.divide_by(&self.gcd(other)).unwrap();
+        (&q * other).monic()
     }
-    pub fn deg(&self) -> i64 {
-        let default = Field::new(0, unsafe { self.factors.get_unchecked(0).n });
-        for (i, d) in self.factors.iter().enumerate().rev() {
-            if *d != default {
-                return i as i64;
-            }
+    /// `m` を法とする逆元. 次数は `deg m` 未満. `self` と `m` が互いに素でないときは `None`
+    /// # Panics
+    /// 係数の法が一致しないとき
+    /// # Example
+    /// ```
+    /// # use finite_field::manipulative::Manipulative;
+    /// # use finite_field::modulo::Field;
+    /// let f = |v: &[i64]| Manipulative::new(v.iter().map(|v| Field::new(*v, 2)).collect());
+    /// // GF(2)[x] / (x^2 + x + 1) で x の逆元は x + 1
+    /// assert_eq!(f(&[0, 1]).inverse_mod(&f(&[1, 1, 1])), Some(f(&[1, 1])));
+    /// assert_eq!(f(&[1, 1]).inverse_mod(&f(&[1, 0, 1])), None);
+    /// ```
+    pub fn inverse_mod(&self, m: &Manipulative<T>) -> Option<Manipulative<T>> {
+        self.check_modulus(m).expect("法が一致しない");
+        if m.is_zero() {
+            return None;
         }
-        -1 // -inftyでも-1とする
+        let (g, s, _) = self.xgcd(m);
+        if g.deg() != Some(0) {
+            return None;
+        }
+        Some(s.divide_by(m).unwrap().1)
     }
-    pub fn diff(&self) -> Manipulative<Field> {
-        if self.factors.len() == 1 {
-            return Manipulative::new(vec![Field::new(0, unsafe {
-                self.factors.get_unchecked(0).n
-            })]);
+    /// x^k で割った商
+    fn shift_right(&self, k: usize) -> Manipulative<T> {
+        match self.factors.get(k..) {
+            Some(f) if !f.is_empty() => Manipulative::new(f.to_vec()),
+            _ => Manipulative::new(vec![self.factors[0].zero_like()]),
         }
-        let mut new_factors = Vec::with_capacity(self.factors.len() - 1);
-        for (i, a) in self.factors.iter().enumerate() {
-            if i != 0 {
-                new_factors.push(*a * i as i64);
-            }
+    }
+}
+/// 多項式の2x2行列 [[a, b], [c, d]]
+struct Matrix<T>([Manipulative<T>; 4]);
+impl<T: FieldElement> Matrix<T> {
+    fn identity(x: &T) -> Self {
+        let zero = Manipulative::new(vec![x.zero_like()]);
+        let one = Manipulative::new(vec![x.one_like()]);
+        Matrix([one.clone(), zero.clone(), zero, one])
+    }
+    /// 剰余列を1つ進める [[0, 1], [1, -q]]
+    fn quotient(q: &Manipulative<T>) -> Self {
+        let x = &q.factors[0];
+        let zero = Manipulative::new(vec![x.zero_like()]);
+        let one = Manipulative::new(vec![x.one_like()]);
+        Matrix([zero.clone(), one.clone(), one, zero - q])
+    }
+    fn mul(&self, other: &Matrix<T>) -> Self {
+        let [a, b, c, d] = &self.0;
+        let [e, f, g, h] = &other.0;
+        Matrix([
+            a * e + &(b * g),
+            a * f + &(b * h),
+            c * e + &(d * g),
+            c * f + &(d * h),
+        ])
+    }
+    fn apply(
+        &self,
+        x: &Manipulative<T>,
+        y: &Manipulative<T>,
+    ) -> (Manipulative<T>, Manipulative<T>) {
+        let [a, b, c, d] = &self.0;
+        (a * x + &(b * y), c * x + &(d * y))
+    }
+}
+/// 半GCD. `deg a > deg b` のとき, 剰余列を (a, b) から (c, d) まで進める行列を返す.
+/// ただし `deg c >= ceil(deg a / 2) > deg d`.
+fn half_gcd<T: FieldElement>(a: &Manipulative<T>, b: &Manipulative<T>) -> Matrix<T> {
+    let n = a.factors.len() - 1;
+    let m = n.div_ceil(2);
+    if b.deg().is_none_or(|d| d < m) {
+        return Matrix::identity(&a.factors[0]);
+    }
+    let r = half_gcd(&a.shift_right(m), &b.shift_right(m));
+    let (c, d) = r.apply(a, b);
+    if d.deg().is_none_or(|d| d < m) {
+        return r;
+    }
+    let (q, e) = c.divide_by(&d).unwrap();
+    let k = 2 * m - (d.factors.len() - 1);
+    let s = half_gcd(&d.shift_right(k), &e.shift_right(k));
+    s.mul(&Matrix::quotient(&q)).mul(&r)
+}
+impl Manipulative<Field> {
+    /// 係数の法がすべて一致することを確かめてから作る.
+    /// # Panics
+    /// 係数が空のとき
+    pub fn try_new(factors: Vec<Field>) -> Result<Self, PolyError> {
+        let result = Self::new(factors);
+        result.modulus()?;
+        Ok(result)
+    }
+    /// 係数の法. 係数の法が一致しないときは `Err`
+    pub fn modulus(&self) -> Result<u64, PolyError> {
+        self.check_modulus(self)?;
+        Ok(self.factors[0].modulus())
+    }
+    /// GF(p) 上の相異なる根を昇順に返す. 0多項式のときは空.
+    ///
+    /// pが小さいときは全数探索, 大きいときは gcd(f, x^p - x) をCantor-Zassenhaus法で分解する.
+    /// # Example
+    /// ```
+    /// # use finite_field::manipulative::Manipulative;
+    /// # use finite_field::modulo::Field;
+    /// let p = 1_000_000_007;
+    /// // (x - 3)^2 (x - 5) (x^2 + 1)
+    /// let f = Manipulative::new([-45, 39, -56, 40, -11, 1].iter().map(|v| Field::new(*v, p)).collect());
+    /// assert_eq!(f.roots(), [Field::new(3, p), Field::new(5, p)]);
+    /// ```
+    pub fn roots(&self) -> Vec<Field> {
+        let p = self.factors[0].modulus();
+        if self.deg().unwrap_or(0) == 0 {
+            return Vec::new();
+        }
+        let mut roots = if p <= 1 << 10 {
+            (0..p as i64)
+                .map(|v| Field::new(v, p))
+                .filter(|x| self.assign(*x).is_zero())
+                .collect()
+        } else {
+            // gcd(f, x^p - x) は f の相異なる1次因子の積
+            let x = Manipulative::new(vec![Field::new(0, p), Field::new(1, p)]);
+            let g = self.gcd(&(pow_mod(&x, p, self) - &x));
+            let mut roots = Vec::new();
+            split_linear_factors(&g, &mut roots);
+            roots
+        };
+        roots.sort();
+        roots
+    }
+    /// 足し算. 係数の法が一致しないときは `Err`
+    pub fn checked_add(&self, other: &Manipulative<Field>) -> Result<Self, PolyError> {
+        self.check_modulus(other)?;
+        Ok(self.clone() + other)
+    }
+    /// 引き算. 係数の法が一致しないときは `Err`
+    pub fn checked_sub(&self, other: &Manipulative<Field>) -> Result<Self, PolyError> {
+        self.check_modulus(other)?;
+        Ok(self.clone() - other)
+    }
+    /// 掛け算. 係数の法が一致しないときは `Err`
+    pub fn checked_mul(&self, other: &Manipulative<Field>) -> Result<Self, PolyError> {
+        self.check_modulus(other)?;
+        Ok(self * other)
+    }
+}
+/// `a` を `m` で割った余り
+fn rem(a: &Manipulative<Field>, m: &Manipulative<Field>) -> Manipulative<Field> {
+    a.divide_by(m).unwrap().1
+}
+/// `a^exp mod m`
+fn pow_mod(a: &Manipulative<Field>, mut exp: u64, m: &Manipulative<Field>) -> Manipulative<Field> {
+    let mut base = rem(a, m);
+    let mut acc = Manipulative::new(vec![Field::new(1, m.factors[0].modulus())]);
+    while exp > 0 {
+        if exp & 1 == 1 {
+            acc = rem(&(&acc * &base), m);
+        }
+        base = rem(&(&base * &base), m);
+        exp >>= 1;
+    }
+    acc
+}
+/// 相異なる1次式の積であるモニックな `g` の根を `roots` に加える
+fn split_linear_factors(g: &Manipulative<Field>, roots: &mut Vec<Field>) {
+    let p = g.factors[0].modulus();
+    match g.deg() {
+        None | Some(0) => return,
+        Some(1) => return roots.push(-g.factors[0]),
+        _ => {}
+    }
+    // gcd(g, (x + δ)^((p-1)/2) - 1) が自明でない因子になるまで δ を変える
+    for delta in 0..p as i64 {
+        let x = Manipulative::new(vec![Field::new(delta, p), Field::new(1, p)]);
+        let h = pow_mod(&x, (p - 1) / 2, g) - &Manipulative::new(vec![Field::new(1, p)]);
+        let h = g.gcd(&h);
+        if Some(0) < h.deg() && h.deg() < g.deg() {
+            let (q, _) = g.divide_by(&h).unwrap();
+            split_linear_factors(&h, roots);
+            split_linear_factors(&q, roots);
+            return;
         }
-        Manipulative::new(new_factors)
     }
 }
-impl<T> AddAssign<&Manipulative<T>> for Manipulative<T>
-where
-    T: Copy + AddAssign,
-{
+impl<T: Ring> AddAssign<&Manipulative<T>> for Manipulative<T> {
     fn add_assign(&mut self, other: &Manipulative<T>) {
+        self.check_modulus(other).expect("法が一致しない");
         let min_deg;
         let max_deg;
         let is_self_shorter;
@@ -194,21 +484,20 @@ where
             is_self_shorter = false;
         }
         for (l, r) in self.factors.iter_mut().zip(other.factors.iter()) {
-            *l += *r;
+            *l += r.clone();
         }
         if is_self_shorter {
             for i in min_deg..max_deg {
                 self.factors
-                    .push(unsafe { *other.factors.get_unchecked(i) })
+                    .push(unsafe { other.factors.get_unchecked(i) }.clone())
             }
         }
+        trim(&mut self.factors);
     }
 }
-impl<T> SubAssign<&Manipulative<T>> for Manipulative<T>
-where
-    T: Copy + SubAssign + Neg<Output = T>,
-{
+impl<T: Ring> SubAssign<&Manipulative<T>> for Manipulative<T> {
     fn sub_assign(&mut self, other: &Manipulative<T>) {
+        self.check_modulus(other).expect("法が一致しない");
         let min_deg;
         let max_deg;
         let is_self_shorter;
@@ -223,69 +512,25 @@ where
             is_self_shorter = false;
         }
         for (l, r) in self.factors.iter_mut().zip(other.factors.iter()) {
-            *l -= *r;
+            *l -= r.clone();
         }
         if is_self_shorter {
             for i in min_deg..max_deg {
                 self.factors
-                    .push(-unsafe { *other.factors.get_unchecked(i) })
-            }
-        }
-    }
-}
-impl Mul<&Manipulative<Field>> for &Manipulative<Field> {
-    type Output = Manipulative<Field>;
-    fn mul(self, other: &Manipulative<Field>) -> Manipulative<Field> {
-        let deg = self.factors.len() + other.factors.len() - 1;
-        let mut new_factors = Vec::with_capacity(deg);
-        new_factors.resize(
-            deg,
-            Field::new(0, unsafe { self.factors.get_unchecked(0).n }),
-        );
-        for (i, l) in self.factors.iter().enumerate() {
-            for (j, r) in other.factors.iter().enumerate() {
-                unsafe {
-                    *new_factors.get_unchecked_mut(i + j) += *l * *r;
-                }
+                    .push(-unsafe { other.factors.get_unchecked(i) }.clone())
             }
         }
-        Manipulative::new(new_factors)
+        trim(&mut self.factors);
     }
 }
-impl<T> Mul<&Manipulative<T>> for &Manipulative<T>
-where
-    T: std::fmt::Debug
-        + Copy
-        + AddAssign
-        + SubAssign
-        + Neg<Output = T>
-        + Add<Output = T>
-        + Sub<Output = T>
-        + Mul<Output = T>
-        + MulAssign
-        + Div<Output = T>
-        + Default
-        + PartialEq,
-{
+impl<T: Ring> Mul<&Manipulative<T>> for &Manipulative<T> {
     type Output = Manipulative<T>;
     fn mul(self, other: &Manipulative<T>) -> Manipulative<T> {
-        let deg = self.factors.len() + other.factors.len() - 1; // -1-1+1
-        let mut new_factors = Vec::with_capacity(deg);
-        new_factors.resize_with(deg, Default::default);
-        for (i, l) in self.factors.iter().enumerate() {
-            for (j, r) in other.factors.iter().enumerate() {
-                unsafe {
-                    *new_factors.get_unchecked_mut(i + j) += *l * *r;
-                }
-            }
-        }
-        Manipulative::new(new_factors)
+        self.check_modulus(other).expect("法が一致しない");
+        Manipulative::new(multiply(&self.factors, &other.factors))
     }
 }
-impl<T> Add<&Manipulative<T>> for Manipulative<T>
-where
-    T: Copy + AddAssign,
-{
+impl<T: Ring> Add<&Manipulative<T>> for Manipulative<T> {
     type Output = Manipulative<T>;
 
     fn add(mut self, other: &Manipulative<T>) -> Self {
@@ -293,10 +538,7 @@ where
         self
     }
 }
-impl<T> Sub<&Manipulative<T>> for Manipulative<T>
-where
-    T: Copy + SubAssign + Neg<Output = T>,
-{
+impl<T: Ring> Sub<&Manipulative<T>> for Manipulative<T> {
     type Output = Manipulative<T>;
 
     fn sub(mut self, other: &Manipulative<T>) -> Self {
@@ -304,36 +546,16 @@ where
         self
     }
 }
-impl<T> MulAssign<&Manipulative<T>> for Manipulative<T>
-where
-    T: std::fmt::Debug
-        + Copy
-        + AddAssign
-        + SubAssign
-        + Neg<Output = T>
-        + Add<Output = T>
-        + Sub<Output = T>
-        + Mul<Output = T>
-        + MulAssign
-        + Div<Output = T>
-        + Default
-        + PartialEq,
-{
+impl<T: Ring> MulAssign<&Manipulative<T>> for Manipulative<T> {
     fn mul_assign(&mut self, other: &Manipulative<T>) {
         let new_factors = (&(*self) * other).factors;
         self.factors = new_factors;
     }
 }
-impl MulAssign<&Manipulative<Field>> for Manipulative<Field> {
-    fn mul_assign(&mut self, other: &Manipulative<Field>) {
-        let new_factors = (&(*self) * other).factors;
-        self.factors = new_factors;
-    }
-}
-impl<T: PartialEq + fmt::Display + Default> fmt::Display for Manipulative<T> {
+impl<T: Ring + fmt::Display> fmt::Display for Manipulative<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         for (i, l) in self.factors.iter().enumerate().rev() {
-            if *l != Default::default() {
+            if !l.is_zero() {
                 if i > 1 {
                     let _ = write!(f, "{} x^{} + ", l, i);
                 } else if i == 1 {
@@ -348,29 +570,12 @@ impl<T: PartialEq + fmt::Display + Default> fmt::Display for Manipulative<T> {
         write!(f, "")
     }
 }
-impl fmt::Display for Manipulative<Field> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let default = Field::new(0, unsafe { self.factors.get_unchecked(0).n });
-        for (i, l) in self.factors.iter().enumerate().rev() {
-            if *l != default {
-                if i > 1 {
-                    let _ = write!(f, "{} x^{} + ", (*l).v, i);
-                } else if i == 1 {
-                    let _ = write!(f, "{} x + ", (*l).v);
-                } else {
-                    let _ = write!(f, "{}", (*l).v);
-                }
-            } else if i == 0 {
-                let _ = write!(f, "{}", (*l).v);
-            }
-        }
-        write!(f, "")
-    }
-}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::convolution::schoolbook;
+    use crate::test_util::Rng;
 
     #[test]
     fn add_test() {
@@ -442,8 +647,242 @@ mod tests {
         );
     }
     #[test]
+    fn div_error_test() {
+        let a = Manipulative::new(vec![1, 2, 1]);
+        assert_eq!(
+            a.divide_by(&Manipulative::new(vec![0])),
+            Err(PolyError::DivisionByZero)
+        );
+        // 整数係数で最高次の係数が割り切れない
+        assert_eq!(
+            a.divide_by(&Manipulative::new(vec![1, 2])),
+            Err(PolyError::NonInvertibleLeadingCoefficient)
+        );
+        let b = Manipulative::new(vec![2, 4, 2]);
+        assert_eq!(
+            b.divide_by(&Manipulative::new(vec![2, 2])).unwrap(),
+            (Manipulative::new(vec![1, 1]), Manipulative::new(vec![0]))
+        );
+        assert_eq!(
+            PolyError::DivisionByZero.to_string(),
+            "division by zero polynomial"
+        );
+    }
+    #[test]
+    fn mismatch_test() {
+        let f = |v: &[i64], n| Manipulative::new(v.iter().map(|v| Field::new(*v, n)).collect());
+        let mixed = vec![Field::new(1, 5), Field::new(1, 7)];
+        assert_eq!(
+            Manipulative::try_new(mixed.clone()),
+            Err(PolyError::ModulusMismatch(5, 7))
+        );
+        assert_eq!(
+            Manipulative::new(mixed).modulus(),
+            Err(PolyError::ModulusMismatch(5, 7))
+        );
+        let (a, b) = (f(&[1, 2], 5), f(&[1, 2, 3], 7));
+        let err = Err(PolyError::ModulusMismatch(5, 7));
+        assert_eq!(a.checked_add(&b), err);
+        assert_eq!(a.checked_sub(&b), err);
+        assert_eq!(a.checked_mul(&b), err);
+        assert_eq!(a.divide_by(&b), Err(PolyError::ModulusMismatch(5, 7)));
+        // 割られる式の方が短くても, 割る式が0でも法を調べる
+        assert_eq!(b.divide_by(&a), Err(PolyError::ModulusMismatch(7, 5)));
+        assert_eq!(
+            a.divide_by(&f(&[0], 7)),
+            Err(PolyError::ModulusMismatch(5, 7))
+        );
+        assert_eq!(
+            FieldError::from(PolyError::ModulusMismatch(5, 7)),
+            FieldError::ModulusMismatch(5, 7)
+        );
+        let b = f(&[1, 2, 3], 5);
+        assert_eq!(a.checked_add(&b), Ok(f(&[2, 4, 3], 5)));
+        assert_eq!(a.checked_sub(&b), Ok(f(&[0, 0, 2], 5)));
+        assert_eq!(a.checked_mul(&b), Ok(f(&[1, 4, 2, 1], 5)));
+        assert_eq!(b.divide_by(&a), Ok((f(&[4, 4], 5), f(&[2], 5))));
+    }
+    #[test]
+    #[should_panic(expected = "法が一致しない")]
+    fn gcd_mismatch_test() {
+        let f = |v: &[i64], n| Manipulative::new(v.iter().map(|v| Field::new(*v, n)).collect());
+        f(&[1, 2], 5).gcd(&f(&[0], 7));
+    }
+    #[test]
+    #[should_panic(expected = "法が一致しない")]
+    fn add_mismatch_test() {
+        let f = |v: &[i64], n| Manipulative::new(v.iter().map(|v| Field::new(*v, n)).collect());
+        let _ = f(&[1], 5) + &f(&[0, 1], 7);
+    }
+    #[test]
+    #[should_panic(expected = "法が一致しない")]
+    fn mul_mismatch_test() {
+        let f = |v: &[i64], n| Manipulative::new(v.iter().map(|v| Field::new(*v, n)).collect());
+        let _ = &f(&[1, 2], 5) * &f(&[3, 1], 7);
+    }
+    #[test]
+    #[should_panic(expected = "法が一致しない")]
+    fn mul_assign_mismatch_test() {
+        let f = |v: &[i64], n| Manipulative::new(v.iter().map(|v| Field::new(*v, n)).collect());
+        let mut a = f(&[1, 2], 5);
+        a *= &f(&[3, 1], 7);
+    }
+    #[test]
+    fn normalize_test() {
+        assert_eq!(Manipulative::new(vec![1, 0]), Manipulative::new(vec![1]));
+        assert_eq!(Manipulative::new(vec![0, 0, 0]).factors(), [0]);
+        assert_eq!(Manipulative::new(vec![0, 0]).deg(), None);
+        assert_eq!(Manipulative::new(vec![5]).deg(), Some(0));
+        let f = |v: &[i64]| Manipulative::new(v.iter().map(|v| Field::new(*v, 5)).collect());
+        assert_eq!(f(&[1, 2, 0, 5]).deg(), Some(1));
+        // 最高次が打ち消し合うと次数が下がる
+        let (a, b) = (f(&[1, 2, 3]), f(&[4, 2, 3]));
+        assert_eq!((a.clone() - &b).factors(), [Field::new(2, 5)]);
+        assert!((a.clone() - &a).is_zero());
+        let (_, r) = f(&[1, 2, 1]).divide_by(&f(&[1, 1, 0])).unwrap();
+        assert!(r.is_zero());
+        assert_eq!(r.deg(), None);
+        assert_eq!(
+            f(&[3, 1]).factors_padded(4),
+            f(&[3, 1, 0, 0]).factors_padded(4)
+        );
+    }
+    #[test]
+    fn gcd_test() {
+        let mut rng = Rng(0x1234_5678_9abc_def1);
+        let p = 97;
+        for &(dg, du, dv) in &[
+            (0, 3, 5),
+            (2, 5, 5),
+            (5, 0, 7),
+            (10, 60, 40),
+            (30, 150, 200),
+        ] {
+            let g = rng.poly(dg, p).monic();
+            let (a, b) = (&g * &rng.poly(du, p), &g * &rng.poly(dv, p));
+            let (h, s, t) = a.xgcd(&b);
+            assert_eq!(&s * &a + &(&t * &b), h);
+            assert!(a.divide_by(&h).unwrap().1.is_zero());
+            assert!(b.divide_by(&h).unwrap().1.is_zero());
+            // 単純なユークリッドの互除法と比べる
+            let (mut r0, mut r1) = (a.clone(), b.clone());
+            while !r1.is_zero() {
+                let (_, r) = r0.divide_by(&r1).unwrap();
+                r0 = std::mem::replace(&mut r1, r);
+            }
+            assert_eq!(h, r0.monic());
+            assert_eq!(h.factors().last(), Some(&Field::new(1, p)));
+            let dh = h.deg().unwrap();
+            assert!(s.deg().is_none_or(|d| d + dh < b.deg().unwrap()));
+            assert!(t.deg().is_none_or(|d| d + dh < a.deg().unwrap()));
+            assert_eq!(&a.lcm(&b) * &h, (&a * &b).monic());
+        }
+        let zero = Manipulative::new(vec![Field::new(0, p)]);
+        let a = rng.poly(4, p);
+        assert_eq!(a.gcd(&zero), a.monic());
+        assert_eq!(zero.gcd(&a), a.monic());
+        assert!(zero.gcd(&zero).is_zero());
+        assert!(a.lcm(&zero).is_zero());
+    }
+    #[test]
+    fn inverse_mod_test() {
+        let mut rng = Rng(0x0f1e_2d3c_4b5a_6978);
+        let p = 101;
+        let m = rng.poly(150, p);
+        for _ in 0..5 {
+            let a = rng.poly(120, p);
+            match a.inverse_mod(&m) {
+                Some(b) => {
+                    assert!(b.deg() < m.deg());
+                    let (_, r) = (&a * &b).divide_by(&m).unwrap();
+                    assert_eq!(r, Manipulative::new(vec![Field::new(1, p)]));
+                }
+                None => assert!(a.gcd(&m).deg() > Some(0)),
+            }
+        }
+        assert_eq!(
+            m.inverse_mod(&Manipulative::new(vec![Field::new(0, p)])),
+            None
+        );
+        // 有理数係数でも使える
+        use crate::rational::Rational;
+        let f = |v: &[i64]| Manipulative::new(v.iter().map(|v| Rational::from(*v)).collect());
+        let inv = f(&[0, 1]).inverse_mod(&f(&[1, 0, 1])).unwrap(); // x^(-1) = -x mod x^2 + 1
+        assert_eq!(inv, f(&[0, -1]));
+    }
+    #[test]
+    fn fast_mul_test() {
+        let mut rng = Rng(0x7777_1234_abcd_0001);
+        // NTT向きの素数, 一般の素数, 64ビットの素数
+        for &p in &[998_244_353, 1_000_000_007, (1 << 61) - 1] {
+            for &(dl, dr) in &[(10, 200), (100, 100), (700, 301)] {
+                let (a, b) = (rng.poly(dl, p), rng.poly(dr, p));
+                let c = &a * &b;
+                assert_eq!(c.factors, schoolbook(&a.factors, &b.factors));
+                assert_eq!(c.deg(), Some(dl + dr));
+            }
+        }
+        let a = Manipulative::new((1..=100).collect::<Vec<i64>>());
+        assert_eq!((&a * &a).factors, schoolbook(&a.factors, &a.factors));
+    }
+    #[test]
+    fn newton_div_test() {
+        let mut rng = Rng(0x3141_5926_5358_9793);
+        for &p in &[998_244_353, 1_000_000_007, 97] {
+            for &(da, db) in &[(300, 100), (500, 64), (1000, 900), (100, 150)] {
+                let (a, b) = (rng.poly(da, p), rng.poly(db, p));
+                let (q, r) = a.divide_by(&b).unwrap();
+                assert_eq!(&q * &b + &r, a);
+                assert!(r.deg().is_none_or(|d| d < b.deg().unwrap()));
+                // 割り切れるとき
+                let (q, r) = (&a * &b).divide_by(&b).unwrap();
+                assert_eq!(q, a);
+                assert!(r.is_zero());
+            }
+        }
+        // 最高次の係数が単元の整数係数
+        let a = Manipulative::new((0..300).map(|i| i * i % 17 - 8).collect::<Vec<i64>>());
+        let mut b = vec![0i64; 100];
+        b[0] = 2;
+        b[3] = -1;
+        b.push(-1);
+        let b = Manipulative::new(b);
+        let (q, r) = a.divide_by(&b).unwrap();
+        assert_eq!(&q * &b + &r, a);
+        assert!(r.deg().unwrap() < 100);
+    }
+    #[test]
+    fn display_test() {
+        let a = Manipulative::new([-1, 0, -2].iter().map(|v| Field::new(*v, 5)).collect());
+        assert_eq!(a.to_string(), "3 x^2 + 4");
+    }
+    #[test]
     fn assign_test() {
         let a = Manipulative::new(vec![2, -3, 1, 0]);
         assert_eq!(a.assign(3), 2);
     }
+    #[test]
+    fn roots_test() {
+        let f =
+            |v: &[i64], p: u64| Manipulative::new(v.iter().map(|v| Field::new(*v, p)).collect());
+        // (x - 1)(x - 2)(x^2 + x + 1) over GF(5), 末尾の0は無視する
+        assert_eq!(
+            f(&[2, -1, 0, -2, 1, 0], 5).roots(),
+            [Field::new(1, 5), Field::new(2, 5)]
+        );
+        assert!(f(&[0], 5).roots().is_empty());
+        assert_eq!(f(&[1, 0, 1], 2).roots(), [Field::new(1, 2)]);
+        // 大きい素数では gcd(f, x^p - x) を分解する
+        let p = (1 << 61) - 1;
+        let expected: Vec<Field> = (1..=12).map(|i| Field::new(i * i * 1_000_003, p)).collect();
+        // x^2 + 1 は既約, x + 1 の根は -1
+        let mut g = &f(&[1, 0, 1], p) * &f(&[1, 1], p);
+        for r in &expected {
+            g *= &Manipulative::new(vec![-*r, Field::new(1, p)]);
+        }
+        let mut expected = expected;
+        expected.push(Field::new(-1, p));
+        expected.sort();
+        assert_eq!(g.roots(), expected);
+    }
 }
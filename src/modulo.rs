@@ -1,65 +1,271 @@
+use super::traits::{EuclideanDomain, FieldElement, Ring};
+use std::error;
+use std::fmt;
 use std::ops;
-#[derive(Copy, Clone, Debug, PartialEq)]
+/// 有限体の演算で起こるエラー.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FieldError {
+    /// 法が0
+    ZeroModulus,
+    /// 法が素数でない
+    NotPrime(u64),
+    /// 逆元が存在しない
+    NotInvertible,
+    /// 法が一致しない
+    ModulusMismatch(u64, u64),
+    /// 法の多項式が既約でない
+    NotIrreducible,
+    /// 法の多項式が原始多項式でない
+    NotPrimitive,
+}
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FieldError::ZeroModulus => write!(f, "modulus is zero"),
+            FieldError::NotPrime(n) => write!(f, "modulus {} is not prime", n),
+            FieldError::NotInvertible => write!(f, "element is not invertible"),
+            FieldError::ModulusMismatch(l, r) => write!(f, "modulus mismatch: {} and {}", l, r),
+            FieldError::NotIrreducible => write!(f, "modulus polynomial is not irreducible"),
+            FieldError::NotPrimitive => write!(f, "modulus polynomial is not primitive"),
+        }
+    }
+}
+impl error::Error for FieldError {}
+/// 素体 GF(n) の元. `n` は素数とする (合成数を法とするなら `Zmod` を使う).
+///
+/// 値は常に `0..n` に正規化されている.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Field {
-    pub v: i64,
-    pub n: u64,
+    v: u64,
+    n: u64,
 }
 impl Field {
+    /// 法の検査は行わない.
+    /// # Panics
+    /// `n == 0` のとき
     pub fn new(value: i64, n: u64) -> Self {
+        if n == 0 {
+            panic!("法が0");
+        }
         Self {
-            v: value % (n as i64),
-            n: n,
+            v: reduce(value, n),
+            n,
+        }
+    }
+    /// `n` が素数であることを確かめてから作る.
+    /// # Example
+    /// ```
+    /// # use finite_field::modulo::{Field, FieldError};
+    /// assert_eq!(Field::try_new(3, 7), Ok(Field::new(3, 7)));
+    /// assert_eq!(Field::try_new(3, 0), Err(FieldError::ZeroModulus));
+    /// assert_eq!(Field::try_new(3, 8), Err(FieldError::NotPrime(8)));
+    /// ```
+    pub fn try_new(value: i64, n: u64) -> Result<Self, FieldError> {
+        if n == 0 {
+            return Err(FieldError::ZeroModulus);
+        }
+        if !is_prime(n) {
+            return Err(FieldError::NotPrime(n));
+        }
+        Ok(Self::new(value, n))
+    }
+    /// `0..n` に正規化済みの値から作る
+    pub(crate) fn from_reduced(v: u64, n: u64) -> Self {
+        debug_assert!(v < n);
+        Self { v, n }
+    }
+    /// `0..n` に正規化された値
+    pub fn value(self) -> u64 {
+        self.v
+    }
+    /// 法
+    pub fn modulus(self) -> u64 {
+        self.n
+    }
+    fn check_modulus(self, other: Field) -> Result<(), FieldError> {
+        if self.n != other.n {
+            return Err(FieldError::ModulusMismatch(self.n, other.n));
         }
+        Ok(())
+    }
+    /// 足し算. 法が一致しないときは `Err`
+    pub fn checked_add(self, other: Field) -> Result<Self, FieldError> {
+        self.check_modulus(other)?;
+        Ok(self + other)
+    }
+    /// 引き算. 法が一致しないときは `Err`
+    pub fn checked_sub(self, other: Field) -> Result<Self, FieldError> {
+        self.check_modulus(other)?;
+        Ok(self - other)
+    }
+    /// 掛け算. 法が一致しないときは `Err`
+    pub fn checked_mul(self, other: Field) -> Result<Self, FieldError> {
+        self.check_modulus(other)?;
+        Ok(self * other)
+    }
+    /// 逆元. 拡張ユークリッドの互除法で求めるので, `n` が合成数でも単元なら求まる.
+    /// 逆元が存在しないときは `Err`
+    pub fn checked_inv(self) -> Result<Self, FieldError> {
+        match inv_mod(self.v, self.n) {
+            Some(v) => Ok(Self { v, n: self.n }),
+            None => Err(FieldError::NotInvertible),
+        }
+    }
+    /// 割り算. 法が一致しないときや0で割るときは `Err`
+    pub fn checked_div(self, other: Field) -> Result<Self, FieldError> {
+        self.check_modulus(other)?;
+        Ok(self * other.checked_inv()?)
     }
 }
-impl ops::Neg for Field {
-    type Output = Field;
-    fn neg(self) -> Self {
+impl Ring for Field {
+    fn zero_like(&self) -> Self {
+        Self { v: 0, n: self.n }
+    }
+    fn one_like(&self) -> Self {
+        Self::new(1, self.n)
+    }
+    fn is_zero(&self) -> bool {
+        self.v == 0
+    }
+    fn mul_usize(&self, k: usize) -> Self {
         Self {
-            v: (self.n as i64 - self.v) % self.n as i64,
+            v: mul_mod(self.v, k as u64 % self.n, self.n),
             n: self.n,
         }
     }
 }
-impl ops::Not for Field {
-    type Output = Field;
-    fn not(self) -> Self {
-        if self.n == 2 {
-            Self {
-                v: (self.v == 0) as i64,
-                n: 2,
-            }
-        } else {
-            Self {
-                v: pow(self.v, (self.n - 2) as u64, self.n as i64),
-                n: self.n,
+impl EuclideanDomain for Field {
+    fn div_rem(&self, other: &Self) -> (Self, Self) {
+        (*self / *other, self.zero_like())
+    }
+}
+impl FieldElement for Field {
+    fn inv(&self) -> Option<Self> {
+        self.checked_inv().ok()
+    }
+}
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.v)
+    }
+}
+/// `value` を `0..n` に収める
+#[inline]
+pub(crate) fn reduce(value: i64, n: u64) -> u64 {
+    (value as i128).rem_euclid(n as i128) as u64
+}
+#[inline]
+pub(crate) fn add_mod(a: u64, b: u64, n: u64) -> u64 {
+    let (s, overflow) = a.overflowing_add(b);
+    if overflow || s >= n {
+        s.wrapping_sub(n)
+    } else {
+        s
+    }
+}
+#[inline]
+pub(crate) fn sub_mod(a: u64, b: u64, n: u64) -> u64 {
+    if a >= b {
+        a - b
+    } else {
+        a.wrapping_sub(b).wrapping_add(n)
+    }
+}
+/// `a` の `n` を法とする逆元を拡張ユークリッドの互除法で求める.
+/// `gcd(a, n) != 1` のときは `None`
+pub(crate) fn inv_mod(a: u64, n: u64) -> Option<u64> {
+    let (mut r0, mut r1) = (n as i128, (a % n) as i128);
+    let (mut t0, mut t1) = (0i128, 1i128);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (t0, t1) = (t1, t0 - q * t1);
+    }
+    if r0 != 1 {
+        return None;
+    }
+    Some(t0.rem_euclid(n as i128) as u64)
+}
+/// `n` が素数かどうかを決定的Miller-Rabin法で判定する.
+pub const fn is_prime(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    let mut i = 0;
+    while i < BASES.len() {
+        if n.is_multiple_of(BASES[i]) {
+            return n == BASES[i];
+        }
+        i += 1;
+    }
+    let mut d = n - 1;
+    let mut s = 0;
+    while d & 1 == 0 {
+        d /= 2;
+        s += 1;
+    }
+    let mut i = 0;
+    'bases: while i < BASES.len() {
+        let mut x = pow_mod(BASES[i], d, n);
+        i += 1;
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        let mut r = 1;
+        while r < s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                continue 'bases;
             }
+            r += 1;
         }
+        return false;
     }
+    true
 }
+/// `a * b mod n` をu128に広げて計算する
 #[inline]
-fn pow(mut base: i64, mut exp: u64, p: i64) -> i64 {
-    let mut acc = 1;
-
-    while exp > 1 {
-        if (exp & 1) == 1 {
-            acc = acc * base % p;
+pub(crate) const fn mul_mod(a: u64, b: u64, n: u64) -> u64 {
+    (a as u128 * b as u128 % n as u128) as u64
+}
+pub(crate) const fn pow_mod(mut base: u64, mut exp: u64, n: u64) -> u64 {
+    let mut acc = 1 % n;
+    base %= n;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            acc = mul_mod(acc, base, n);
         }
-        exp /= 2;
-        base = base * base % p;
-    }
-    if exp == 1 {
-        acc = acc * base % p;
+        base = mul_mod(base, base, n);
+        exp >>= 1;
     }
     acc
 }
+impl ops::Neg for Field {
+    type Output = Field;
+    fn neg(self) -> Self {
+        Self {
+            v: sub_mod(0, self.v, self.n),
+            n: self.n,
+        }
+    }
+}
+/// 逆元.
+/// # Panics
+/// 逆元が存在しないとき
+impl ops::Not for Field {
+    type Output = Field;
+    fn not(self) -> Self {
+        self.checked_inv().expect("逆元が存在しない")
+    }
+}
 impl ops::Add for Field {
     type Output = Field;
 
     fn add(self, other: Field) -> Self {
-        // assert_eq!(self.n, other.n);
+        debug_assert_eq!(self.n, other.n, "法が一致しない");
         Self {
-            v: (self.v + other.v) % (self.n as i64),
+            v: add_mod(self.v, other.v, self.n),
             n: self.n,
         }
     }
@@ -69,39 +275,39 @@ impl ops::Add<i64> for Field {
 
     fn add(self, other: i64) -> Self {
         Self {
-            v: (self.v + other) % (self.n as i64),
+            v: add_mod(self.v, reduce(other, self.n), self.n),
             n: self.n,
         }
     }
 }
 impl ops::AddAssign for Field {
     fn add_assign(&mut self, other: Field) {
-        // assert_eq!(self.n, other.n);
-        self.v = (self.v + other.v) % (self.n as i64);
+        debug_assert_eq!(self.n, other.n, "法が一致しない");
+        self.v = add_mod(self.v, other.v, self.n);
     }
 }
 impl ops::Sub for Field {
     type Output = Field;
     fn sub(self, other: Field) -> Self {
-        // assert_eq!(self.n, other.n);
+        debug_assert_eq!(self.n, other.n, "法が一致しない");
         Self {
-            v: (self.v - other.v + self.n as i64) % (self.n as i64),
+            v: sub_mod(self.v, other.v, self.n),
             n: self.n,
         }
     }
 }
 impl ops::SubAssign for Field {
     fn sub_assign(&mut self, other: Field) {
-        // assert_eq!(self.n, other.n);
-        self.v = (self.v - other.v + self.n as i64) % (self.n as i64);
+        debug_assert_eq!(self.n, other.n, "法が一致しない");
+        self.v = sub_mod(self.v, other.v, self.n);
     }
 }
 impl ops::Mul for Field {
     type Output = Field;
     fn mul(self, other: Field) -> Self {
-        // assert_eq!(self.n, other.n);
+        debug_assert_eq!(self.n, other.n, "法が一致しない");
         Self {
-            v: self.v * other.v % (self.n as i64),
+            v: mul_mod(self.v, other.v, self.n),
             n: self.n,
         }
     }
@@ -111,32 +317,32 @@ impl ops::Mul<i64> for Field {
 
     fn mul(self, other: i64) -> Self {
         Self {
-            v: (self.v * other) % (self.n as i64),
+            v: mul_mod(self.v, reduce(other, self.n), self.n),
             n: self.n,
         }
     }
 }
 impl ops::MulAssign for Field {
     fn mul_assign(&mut self, other: Field) {
-        // assert_eq!(self.n, other.n);
-        self.v = (self.v * other.v) % (self.n as i64);
+        debug_assert_eq!(self.n, other.n, "法が一致しない");
+        self.v = mul_mod(self.v, other.v, self.n);
     }
 }
 impl ops::MulAssign<i64> for Field {
     fn mul_assign(&mut self, other: i64) {
-        self.v = (self.v * other) % (self.n as i64);
+        self.v = mul_mod(self.v, reduce(other, self.n), self.n);
     }
 }
 impl ops::Div for Field {
     type Output = Field;
     fn div(self, other: Field) -> Self {
-        // assert_eq!(self.n, other.n);
+        debug_assert_eq!(self.n, other.n, "法が一致しない");
         self * (!other)
     }
 }
 impl ops::DivAssign for Field {
     fn div_assign(&mut self, other: Field) {
-        // assert_eq!(self.n, other.n);
+        debug_assert_eq!(self.n, other.n, "法が一致しない");
         *self *= !other;
     }
 }
@@ -148,10 +354,15 @@ mod tests {
     fn add_test() {
         let mut x = Field::new(1, 3) + Field::new(4, 3);
         assert_eq!(x, Field::new(2, 3));
-        x += x.clone();
+        x += x;
         assert_eq!(x, Field::new(1, 3));
     }
     #[test]
+    fn accessor_test() {
+        let x = Field::new(-1, 7);
+        assert_eq!((x.value(), x.modulus()), (6, 7));
+    }
+    #[test]
     fn sub_test() {
         let x = Field::new(2, 3) - Field::new(4, 3);
         assert_eq!(x, Field::new(1, 3));
@@ -165,14 +376,132 @@ mod tests {
     fn div_test() {
         let mut x = Field::new(2, 5) / Field::new(3, 5);
         assert_eq!(x, Field::new(4, 5));
-        x /= x.clone();
+        x /= x;
         assert_eq!(x, Field::new(1, 5));
     }
     #[test]
     fn not_test() {
-        let x = !Field::new(1, 2);
-        assert_eq!(x, Field::new(0, 2));
-        assert_eq!(!x, Field::new(1, 2));
+        assert_eq!(!Field::new(1, 2), Field::new(1, 2));
+        assert_eq!(
+            Field::new(0, 2).checked_inv(),
+            Err(FieldError::NotInvertible)
+        );
+        for p in [3, 5, 7, 11, 13] {
+            for v in 1..p as i64 {
+                assert_eq!(!Field::new(v, p) * Field::new(v, p), Field::new(1, p));
+            }
+        }
+        // 合成数でも単元なら逆元が求まる
+        assert_eq!(Field::new(3, 10).checked_inv(), Ok(Field::new(7, 10)));
+        assert_eq!(
+            Field::new(4, 10).checked_inv(),
+            Err(FieldError::NotInvertible)
+        );
+        assert_eq!(Field::new(4, 10).inv(), None);
+        assert_eq!(Field::new(0, 1).checked_inv(), Ok(Field::new(0, 1)));
+    }
+    #[test]
+    #[should_panic(expected = "逆元が存在しない")]
+    fn not_zero_test() {
+        let _ = !Field::new(0, 5);
+    }
+    #[test]
+    fn checked_test() {
+        assert_eq!(Field::try_new(1, 0), Err(FieldError::ZeroModulus));
+        assert_eq!(Field::try_new(1, 1), Err(FieldError::NotPrime(1)));
+        assert_eq!(Field::try_new(1, 91), Err(FieldError::NotPrime(91)));
+        assert_eq!(Field::try_new(-1, 97), Ok(Field::new(-1, 97)));
+        assert_eq!(
+            Field::new(0, 5).checked_inv(),
+            Err(FieldError::NotInvertible)
+        );
+        assert_eq!(Field::new(3, 5).checked_inv(), Ok(Field::new(2, 5)));
+        assert_eq!(
+            Field::new(1, 5).checked_div(Field::new(0, 5)),
+            Err(FieldError::NotInvertible)
+        );
+        assert_eq!(
+            Field::new(1, 5).checked_div(Field::new(1, 7)),
+            Err(FieldError::ModulusMismatch(5, 7))
+        );
+        assert_eq!(
+            Field::new(2, 5).checked_div(Field::new(3, 5)),
+            Ok(Field::new(4, 5))
+        );
+    }
+    #[test]
+    fn mismatch_test() {
+        let (x, y) = (Field::new(1, 5), Field::new(1, 7));
+        let err = Err(FieldError::ModulusMismatch(5, 7));
+        assert_eq!(x.checked_add(y), err);
+        assert_eq!(x.checked_sub(y), err);
+        assert_eq!(x.checked_mul(y), err);
+        assert_eq!(x.checked_div(y), err);
+        assert_eq!(x.checked_add(x), Ok(Field::new(2, 5)));
+        assert_eq!(x.checked_sub(x), Ok(Field::new(0, 5)));
+        assert_eq!(x.checked_mul(x), Ok(Field::new(1, 5)));
+    }
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "法が一致しない")]
+    fn mismatch_panic_test() {
+        let _ = Field::new(1, 5) + Field::new(1, 7);
+    }
+    #[test]
+    fn is_prime_test() {
+        let small: Vec<u64> = (0..50).filter(|n| is_prime(*n)).collect();
+        assert_eq!(
+            small,
+            [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47]
+        );
+        assert!(is_prime((1 << 61) - 1));
+        assert!(is_prime(u64::MAX - 58));
+        assert!(!is_prime(3_215_031_751)); // 強擬素数
+        assert!(!is_prime(u64::MAX));
+    }
+    #[test]
+    fn canonical_test() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+        let hash = |x: Field| {
+            let mut h = DefaultHasher::new();
+            x.hash(&mut h);
+            h.finish()
+        };
+        assert_eq!(Field::new(-1, 5), Field::new(4, 5));
+        assert_eq!(hash(Field::new(-1, 5)), hash(Field::new(9, 5)));
+        assert_eq!(Field::new(-13, 5).v, 2);
+        assert_eq!((-Field::new(0, 5)).v, 0);
+        assert_eq!((Field::new(1, 5) + -8).v, 3);
+        assert_eq!((Field::new(2, 5) * -1).v, 3);
+        let mut x = Field::new(2, 5);
+        x *= -7;
+        assert_eq!(x.v, 1);
+        assert!(Field::new(1, 5) < Field::new(-1, 5));
+        assert_eq!(Field::new(-2, 7).to_string(), "5");
+    }
+    #[test]
+    fn large_modulus_test() {
+        for &p in &[(1u64 << 61) - 1, u64::MAX - 58] {
+            let m1 = Field::new(-1, p);
+            assert_eq!(m1.v, p - 1);
+            assert_eq!(m1 * m1, Field::new(1, p));
+            assert_eq!(m1 + m1, Field::new(-2, p));
+            assert_eq!(m1 - Field::new(-2, p), Field::new(1, p));
+            assert_eq!(-m1, Field::new(1, p));
+            assert_eq!(m1 * -1, Field::new(1, p));
+            assert_eq!(m1 + i64::MAX, Field::new(i64::MAX - 1, p));
+            let mut x = Field::new(-3, p);
+            x *= Field::new(-5, p);
+            assert_eq!(x, Field::new(15, p));
+            x *= i64::MIN;
+            assert_eq!(x / Field::new(i64::MIN, p), Field::new(15, p));
+            let h = Field::new(2, p) * Field::new(1 << 62, p);
+            assert_eq!(h, Field::new(i64::MIN, p) * -1);
+            assert_eq!(!Field::new(2, p) * Field::new(2, p), Field::new(1, p));
+            let y = Field::new(-123_456_789, p);
+            assert_eq!(!y * y, Field::new(1, p));
+        }
     }
     #[test]
     fn neg_test() {
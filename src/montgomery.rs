@@ -0,0 +1,305 @@
+use super::fp::Fp;
+use super::modulo::{add_mod, is_prime, mul_mod, reduce, sub_mod, Field, FieldError};
+use super::traits::{EuclideanDomain, FieldElement, One, Ring, Zero};
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops;
+/// Montgomery表現で値を持つ素体 GF(P) の元.
+///
+/// `R = 2^64` として `v = x R mod P` を保持する. 乗算で `%` を使わないので
+/// 掛け算の多い多項式の計算では `Fp<P>` や `Field` より速い.
+/// `P` は2でない素数でなければならない.
+/// # Example
+/// ```
+/// # use finite_field::montgomery::MontFp;
+/// # use finite_field::modulo::Field;
+/// type F = MontFp<998_244_353>;
+/// let x = F::new(2) / F::new(3);
+/// assert_eq!(x * F::new(3), F::new(2));
+/// assert_eq!(Field::from(F::new(-1)), Field::new(-1, 998_244_353));
+/// ```
+/// ```compile_fail
+/// # use finite_field::montgomery::MontFp;
+/// let x = MontFp::<9>::default();
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MontFp<const P: u64> {
+    v: u64,
+}
+impl<const P: u64> MontFp<P> {
+    const PRIME: () = assert!(P != 2 && is_prime(P), "Pが奇素数でない");
+    /// `-P^{-1} mod 2^64`
+    const NEG_INV: u64 = {
+        let mut inv = P;
+        let mut i = 0;
+        while i < 6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(P.wrapping_mul(inv)));
+            i += 1;
+        }
+        inv.wrapping_neg()
+    };
+    /// `R^2 mod P`
+    const R2: u64 = {
+        let r = ((1u128 << 64) % P as u128) as u64;
+        mul_mod(r, r, P)
+    };
+    pub fn new(value: i64) -> Self {
+        let () = Self::PRIME;
+        Self::from_u64(reduce(value, P))
+    }
+    fn from_u64(value: u64) -> Self {
+        Self {
+            v: Self::redc(value as u128 * Self::R2 as u128),
+        }
+    }
+    /// `t R^{-1} mod P` を求める. `t < P^2` であること.
+    #[inline]
+    fn redc(t: u128) -> u64 {
+        let m = (t as u64).wrapping_mul(Self::NEG_INV);
+        let (s, overflow) = t.overflowing_add(m as u128 * P as u128);
+        let u = (s >> 64) | ((overflow as u128) << 64);
+        if u >= P as u128 {
+            (u - P as u128) as u64
+        } else {
+            u as u64
+        }
+    }
+    /// `0..P` に正規化された値
+    pub fn value(self) -> u64 {
+        Self::redc(self.v as u128)
+    }
+    /// 法
+    pub const fn modulus() -> u64 {
+        P
+    }
+    pub fn pow(self, mut exp: u64) -> Self {
+        let mut base = self;
+        let mut acc = Self::one();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                acc *= base;
+            }
+            base *= base;
+            exp >>= 1;
+        }
+        acc
+    }
+    /// 逆元. 0のときは `Err`
+    pub fn checked_inv(self) -> Result<Self, FieldError> {
+        if self.v == 0 {
+            return Err(FieldError::NotInvertible);
+        }
+        Ok(self.pow(P - 2))
+    }
+    /// 割り算. 0で割るときは `Err`
+    pub fn checked_div(self, other: Self) -> Result<Self, FieldError> {
+        Ok(self * other.checked_inv()?)
+    }
+}
+impl<const P: u64> Ring for MontFp<P> {
+    fn zero_like(&self) -> Self {
+        Self::zero()
+    }
+    fn one_like(&self) -> Self {
+        Self::one()
+    }
+    fn is_zero(&self) -> bool {
+        self.v == 0
+    }
+}
+impl<const P: u64> EuclideanDomain for MontFp<P> {
+    fn div_rem(&self, other: &Self) -> (Self, Self) {
+        (*self / *other, Self::zero())
+    }
+}
+impl<const P: u64> FieldElement for MontFp<P> {
+    fn inv(&self) -> Option<Self> {
+        self.checked_inv().ok()
+    }
+}
+impl<const P: u64> Zero for MontFp<P> {
+    fn zero() -> Self {
+        let () = Self::PRIME;
+        Self { v: 0 }
+    }
+}
+impl<const P: u64> Default for MontFp<P> {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+impl<const P: u64> One for MontFp<P> {
+    fn one() -> Self {
+        Self::new(1)
+    }
+}
+impl<const P: u64> From<MontFp<P>> for Field {
+    fn from(x: MontFp<P>) -> Field {
+        Field::from_reduced(x.value(), P)
+    }
+}
+impl<const P: u64> TryFrom<Field> for MontFp<P> {
+    type Error = FieldError;
+    fn try_from(x: Field) -> Result<Self, FieldError> {
+        if x.modulus() != P {
+            return Err(FieldError::ModulusMismatch(x.modulus(), P));
+        }
+        Ok(Self::from_u64(x.value()))
+    }
+}
+impl<const P: u64> From<Fp<P>> for MontFp<P> {
+    fn from(x: Fp<P>) -> Self {
+        Self::from_u64(x.value())
+    }
+}
+impl<const P: u64> From<MontFp<P>> for Fp<P> {
+    fn from(x: MontFp<P>) -> Self {
+        Fp::<P>::try_from(Field::from(x)).unwrap()
+    }
+}
+impl<const P: u64> fmt::Display for MontFp<P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.value())
+    }
+}
+impl<const P: u64> ops::Neg for MontFp<P> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self {
+            v: sub_mod(0, self.v, P),
+        }
+    }
+}
+/// 逆元.
+/// # Panics
+/// 0のとき
+impl<const P: u64> ops::Not for MontFp<P> {
+    type Output = Self;
+    fn not(self) -> Self {
+        self.checked_inv().expect("逆元が存在しない")
+    }
+}
+impl<const P: u64> ops::Add for MontFp<P> {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Self {
+            v: add_mod(self.v, other.v, P),
+        }
+    }
+}
+impl<const P: u64> ops::Add<i64> for MontFp<P> {
+    type Output = Self;
+    fn add(self, other: i64) -> Self {
+        self + Self::new(other)
+    }
+}
+impl<const P: u64> ops::AddAssign for MontFp<P> {
+    fn add_assign(&mut self, other: Self) {
+        self.v = add_mod(self.v, other.v, P);
+    }
+}
+impl<const P: u64> ops::Sub for MontFp<P> {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Self {
+            v: sub_mod(self.v, other.v, P),
+        }
+    }
+}
+impl<const P: u64> ops::SubAssign for MontFp<P> {
+    fn sub_assign(&mut self, other: Self) {
+        self.v = sub_mod(self.v, other.v, P);
+    }
+}
+impl<const P: u64> ops::Mul for MontFp<P> {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        Self {
+            v: Self::redc(self.v as u128 * other.v as u128),
+        }
+    }
+}
+impl<const P: u64> ops::Mul<i64> for MontFp<P> {
+    type Output = Self;
+    fn mul(self, other: i64) -> Self {
+        self * Self::new(other)
+    }
+}
+impl<const P: u64> ops::MulAssign for MontFp<P> {
+    fn mul_assign(&mut self, other: Self) {
+        self.v = Self::redc(self.v as u128 * other.v as u128);
+    }
+}
+impl<const P: u64> ops::MulAssign<i64> for MontFp<P> {
+    fn mul_assign(&mut self, other: i64) {
+        *self *= Self::new(other);
+    }
+}
+impl<const P: u64> ops::Div for MontFp<P> {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: Self) -> Self {
+        self * !other
+    }
+}
+impl<const P: u64> ops::DivAssign for MontFp<P> {
+    #[allow(clippy::suspicious_op_assign_impl)]
+    fn div_assign(&mut self, other: Self) {
+        *self *= !other;
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manipulative::Manipulative;
+
+    const PRIMES: [u64; 4] = [7, 998_244_353, (1 << 61) - 1, u64::MAX - 58];
+
+    fn check<const P: u64>() {
+        let vals = [0, 1, 2, 3, -1, -2, 123_456_789, i64::MAX, i64::MIN];
+        for &a in &vals {
+            assert_eq!(MontFp::<P>::new(a).value(), Field::new(a, P).value());
+            for &b in &vals {
+                let (x, y) = (MontFp::<P>::new(a), MontFp::<P>::new(b));
+                let (fx, fy) = (Field::new(a, P), Field::new(b, P));
+                assert_eq!(Field::from(x + y), fx + fy);
+                assert_eq!(Field::from(x - y), fx - fy);
+                assert_eq!(Field::from(x * y), fx * fy);
+                if fy.value() != 0 {
+                    assert_eq!(Field::from(x / y), fx / fy);
+                }
+            }
+        }
+    }
+    #[test]
+    fn arith_test() {
+        check::<{ PRIMES[0] }>();
+        check::<{ PRIMES[1] }>();
+        check::<{ PRIMES[2] }>();
+        check::<{ PRIMES[3] }>();
+    }
+    #[test]
+    fn conversion_test() {
+        type F = MontFp<7>;
+        assert_eq!(F::try_from(Field::new(10, 7)), Ok(F::new(3)));
+        assert_eq!(
+            F::try_from(Field::new(1, 5)),
+            Err(FieldError::ModulusMismatch(5, 7))
+        );
+        assert_eq!(Fp::<7>::from(F::new(-1)), Fp::<7>::new(6));
+        assert_eq!(F::from(Fp::<7>::new(4)), F::new(4));
+        assert_eq!(F::zero(), F::default());
+        assert_eq!(F::one().value(), 1);
+        assert_eq!(F::new(-3).to_string(), "4");
+    }
+    #[test]
+    fn manipulative_test() {
+        type F = MontFp<11>;
+        let f = |v: &[i64]| Manipulative::new(v.iter().map(|v| F::new(*v)).collect());
+        let a = f(&[1, 2, 1]);
+        let b = f(&[1, 1]);
+        assert_eq!(&b * &b, a);
+        assert_eq!(a.divide_by(&b).unwrap(), (f(&[1, 1, 0]), f(&[0, 0, 0])));
+        assert_eq!(a.assign(F::new(3)), F::new(16));
+    }
+}
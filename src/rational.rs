@@ -0,0 +1,211 @@
+use super::traits::{EuclideanDomain, FieldElement, One, Ring, Zero};
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops;
+/// 有理数. 常に既約で分母は正.
+/// # Example
+/// ```
+/// # use finite_field::rational::Rational;
+/// let x = Rational::new(1, 2) + Rational::new(1, 3);
+/// assert_eq!(x, Rational::new(10, 12));
+/// assert_eq!(x.to_string(), "5/6");
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Rational {
+    num: i64,
+    den: i64,
+}
+fn gcd(mut a: i128, mut b: i128) -> i128 {
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a.abs()
+}
+impl Rational {
+    /// # Panics
+    /// `den == 0` のとき, 約分した分子か分母が `i64` に収まらないとき (`new(i64::MIN, -1)` など)
+    pub fn new(num: i64, den: i64) -> Self {
+        Self::reduce(num as i128, den as i128)
+    }
+    fn reduce(mut num: i128, mut den: i128) -> Self {
+        if den == 0 {
+            panic!("分母が0");
+        }
+        if den < 0 {
+            num = -num;
+            den = -den;
+        }
+        let g = gcd(num, den);
+        Self {
+            num: i64::try_from(num / g).expect("オーバーフロー"),
+            den: i64::try_from(den / g).expect("オーバーフロー"),
+        }
+    }
+    /// 分子
+    pub fn numer(self) -> i64 {
+        self.num
+    }
+    /// 分母
+    pub fn denom(self) -> i64 {
+        self.den
+    }
+}
+impl From<i64> for Rational {
+    fn from(v: i64) -> Self {
+        Self { num: v, den: 1 }
+    }
+}
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.den == 1 {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+impl ops::Neg for Rational {
+    type Output = Rational;
+    fn neg(self) -> Self {
+        Self {
+            num: self.num.checked_neg().expect("オーバーフロー"),
+            den: self.den,
+        }
+    }
+}
+impl ops::Add for Rational {
+    type Output = Rational;
+    fn add(self, other: Rational) -> Self {
+        let (a, b, c, d) = (
+            self.num as i128,
+            self.den as i128,
+            other.num as i128,
+            other.den as i128,
+        );
+        Self::reduce(a * d + b * c, b * d)
+    }
+}
+impl ops::AddAssign for Rational {
+    fn add_assign(&mut self, other: Rational) {
+        *self = *self + other;
+    }
+}
+impl ops::Sub for Rational {
+    type Output = Rational;
+    fn sub(self, other: Rational) -> Self {
+        self + -other
+    }
+}
+impl ops::SubAssign for Rational {
+    fn sub_assign(&mut self, other: Rational) {
+        *self = *self - other;
+    }
+}
+impl ops::Mul for Rational {
+    type Output = Rational;
+    fn mul(self, other: Rational) -> Self {
+        Self::reduce(
+            self.num as i128 * other.num as i128,
+            self.den as i128 * other.den as i128,
+        )
+    }
+}
+impl ops::MulAssign for Rational {
+    fn mul_assign(&mut self, other: Rational) {
+        *self = *self * other;
+    }
+}
+impl ops::Div for Rational {
+    type Output = Rational;
+    fn div(self, other: Rational) -> Self {
+        Self::reduce(
+            self.num as i128 * other.den as i128,
+            self.den as i128 * other.num as i128,
+        )
+    }
+}
+impl ops::DivAssign for Rational {
+    fn div_assign(&mut self, other: Rational) {
+        *self = *self / other;
+    }
+}
+impl Ring for Rational {
+    fn zero_like(&self) -> Self {
+        Self::zero()
+    }
+    fn one_like(&self) -> Self {
+        Self::one()
+    }
+    fn is_zero(&self) -> bool {
+        self.num == 0
+    }
+}
+impl EuclideanDomain for Rational {
+    fn div_rem(&self, other: &Self) -> (Self, Self) {
+        (*self / *other, Self::zero())
+    }
+}
+impl FieldElement for Rational {
+    fn inv(&self) -> Option<Self> {
+        if self.num == 0 {
+            return None;
+        }
+        Some(Self::one() / *self)
+    }
+}
+impl Zero for Rational {
+    fn zero() -> Self {
+        Self { num: 0, den: 1 }
+    }
+}
+impl One for Rational {
+    fn one() -> Self {
+        Self { num: 1, den: 1 }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manipulative::Manipulative;
+
+    #[test]
+    fn arith_test() {
+        let (a, b) = (Rational::new(1, 2), Rational::new(-2, 3));
+        assert_eq!(a + b, Rational::new(-1, 6));
+        assert_eq!(a - b, Rational::new(7, 6));
+        assert_eq!(a * b, Rational::new(-1, 3));
+        assert_eq!(a / b, Rational::new(3, -4));
+        assert_eq!(Rational::new(3, -4).numer(), -3);
+        assert_eq!(Rational::new(3, -4).denom(), 4);
+        assert_eq!(b.inv(), Some(Rational::new(-3, 2)));
+        assert_eq!(Rational::zero().inv(), None);
+        assert_eq!(a.mul_usize(5), Rational::new(5, 2));
+        assert_eq!(Rational::from(4).to_string(), "4");
+    }
+    #[test]
+    #[should_panic(expected = "オーバーフロー")]
+    fn neg_overflow_test() {
+        let _ = -Rational::from(i64::MIN);
+    }
+    #[test]
+    #[should_panic(expected = "オーバーフロー")]
+    fn new_overflow_test() {
+        let _ = Rational::new(i64::MIN, -1);
+    }
+    #[test]
+    fn manipulative_test() {
+        let f = |v: &[(i64, i64)]| {
+            Manipulative::new(v.iter().map(|&(n, d)| Rational::new(n, d)).collect())
+        };
+        // (x^2 + 1) / (2x + 1) = x/2 - 1/4 ... 5/4
+        let a = f(&[(1, 1), (0, 1), (1, 1)]);
+        let b = f(&[(1, 1), (2, 1)]);
+        let (q, r) = a.divide_by(&b).unwrap();
+        assert_eq!(q, f(&[(-1, 4), (1, 2), (0, 1)]));
+        assert_eq!(r, f(&[(5, 4), (0, 1), (0, 1)]));
+        assert_eq!(a.diff(), f(&[(0, 1), (2, 1)]));
+        assert_eq!(a.to_string(), "1 x^2 + 1");
+    }
+}
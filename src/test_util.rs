@@ -0,0 +1,23 @@
+//! テストで使う補助.
+use super::manipulative::Manipulative;
+use super::modulo::Field;
+
+/// テスト用の擬似乱数 (xorshift)
+pub(crate) struct Rng(pub(crate) u64);
+impl Rng {
+    /// `0..n` の値
+    pub(crate) fn next(&mut self, n: u64) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 % n
+    }
+    /// GF(p) 上の次数が `deg` 以下の多項式
+    pub(crate) fn poly(&mut self, deg: usize, p: u64) -> Manipulative<Field> {
+        Manipulative::new(
+            (0..=deg)
+                .map(|_| Field::new(self.next(p) as i64, p))
+                .collect(),
+        )
+    }
+}
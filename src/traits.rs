@@ -0,0 +1,113 @@
+//! 係数として使う型が満たすトレイト.
+//!
+//! `Manipulative<T>` は `T: Ring` であれば足し算, 掛け算, 代入ができ,
+//! `T: EuclideanDomain` であれば割り算ができる.
+use std::fmt::Debug;
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// 可換環の元.
+///
+/// `Field` のように値ごとに法を持つ型があるので, 単位元は既存の元から作る.
+/// 多項式の演算で `Field` などの係数を型で見分けるので, `'static` とする.
+pub trait Ring:
+    'static
+    + Clone
+    + Debug
+    + PartialEq
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+{
+    /// `self` と同じ環の加法単位元
+    fn zero_like(&self) -> Self;
+    /// `self` と同じ環の乗法単位元
+    fn one_like(&self) -> Self;
+    fn is_zero(&self) -> bool {
+        *self == self.zero_like()
+    }
+    /// `self` を `k` 回足したもの
+    fn mul_usize(&self, mut k: usize) -> Self {
+        let mut acc = self.zero_like();
+        let mut base = self.clone();
+        while k > 0 {
+            if k & 1 == 1 {
+                acc += base.clone();
+            }
+            base += base.clone();
+            k >>= 1;
+        }
+        acc
+    }
+}
+/// ユークリッド整域の元.
+pub trait EuclideanDomain: Ring + Div<Output = Self> {
+    /// 商と余り
+    fn div_rem(&self, other: &Self) -> (Self, Self);
+}
+/// 体の元.
+pub trait FieldElement: EuclideanDomain {
+    /// 逆元. 0のときは `None`
+    fn inv(&self) -> Option<Self>;
+}
+/// 加法単位元を型だけから作れる環.
+pub trait Zero: Ring {
+    fn zero() -> Self;
+}
+/// 乗法単位元を型だけから作れる環.
+pub trait One: Ring {
+    fn one() -> Self;
+}
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Ring for $t {
+                fn zero_like(&self) -> Self {
+                    0
+                }
+                fn one_like(&self) -> Self {
+                    1
+                }
+                fn is_zero(&self) -> bool {
+                    *self == 0
+                }
+                fn mul_usize(&self, k: usize) -> Self {
+                    *self * k as $t
+                }
+            }
+            impl EuclideanDomain for $t {
+                fn div_rem(&self, other: &Self) -> (Self, Self) {
+                    (*self / *other, *self % *other)
+                }
+            }
+            impl Zero for $t {
+                fn zero() -> Self {
+                    0
+                }
+            }
+            impl One for $t {
+                fn one() -> Self {
+                    1
+                }
+            }
+        )*
+    };
+}
+impl_integer!(i8, i16, i32, i64, i128, isize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integer_test() {
+        assert!(0i64.is_zero());
+        assert_eq!(7i64.one_like(), 1);
+        assert_eq!(7i64.mul_usize(3), 21);
+        assert_eq!(7i64.div_rem(&3), (2, 1));
+        assert_eq!(i32::zero() + i32::one(), 1);
+    }
+}
@@ -0,0 +1,391 @@
+use super::modulo::{
+    add_mod, inv_mod, is_prime, mul_mod, pow_mod, reduce, sub_mod, Field, FieldError,
+};
+use super::traits::Ring;
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops;
+/// 剰余環 Z/nZ の元. `n` は合成数でもよい.
+///
+/// `v` は常に `0..n` に正規化されている. 素数を法とするなら `Field` を使う.
+/// # Example
+/// ```
+/// # use finite_field::zmod::{carmichael_lambda, euler_phi, Zmod};
+/// let x = Zmod::new(3, 10);
+/// assert!(x.is_unit());
+/// assert_eq!(x.checked_inv(), Ok(Zmod::new(7, 10)));
+/// assert_eq!(x.order(), Some(4));
+/// assert!(!Zmod::new(4, 10).is_unit());
+/// assert_eq!(euler_phi(10), 4);
+/// assert_eq!(carmichael_lambda(8), 2);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Zmod {
+    pub v: u64,
+    pub n: u64,
+}
+impl Zmod {
+    /// # Panics
+    /// `n == 0` のとき
+    pub fn new(value: i64, n: u64) -> Self {
+        if n == 0 {
+            panic!("法が0");
+        }
+        Self {
+            v: reduce(value, n),
+            n,
+        }
+    }
+    /// 単元かどうか
+    pub fn is_unit(self) -> bool {
+        gcd(self.v, self.n) == 1
+    }
+    /// 逆元. 単元でないときは `Err`
+    pub fn checked_inv(self) -> Result<Self, FieldError> {
+        match inv_mod(self.v, self.n) {
+            Some(v) => Ok(Self { v, n: self.n }),
+            None => Err(FieldError::NotInvertible),
+        }
+    }
+    /// 割り算. 法が一致しないときや単元でないもので割るときは `Err`
+    pub fn checked_div(self, other: Zmod) -> Result<Self, FieldError> {
+        if self.n != other.n {
+            return Err(FieldError::ModulusMismatch(self.n, other.n));
+        }
+        Ok(self * other.checked_inv()?)
+    }
+    pub fn pow(self, exp: u64) -> Self {
+        Self {
+            v: pow_mod(self.v, exp, self.n),
+            n: self.n,
+        }
+    }
+    /// 乗法群での位数. 単元でないときは `None`
+    pub fn order(self) -> Option<u64> {
+        if !self.is_unit() {
+            return None;
+        }
+        // 位数はλ(n)を割り切る
+        let mut order = carmichael_lambda(self.n);
+        for (q, _) in factorize(order) {
+            while order.is_multiple_of(q) && self.pow(order / q).v == 1 % self.n {
+                order /= q;
+            }
+        }
+        Some(order)
+    }
+}
+/// 最大公約数
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+/// 素因数分解. `(素数, 指数)` を素数の昇順で返す. `n <= 1` のときは空.
+/// # Example
+/// ```
+/// # use finite_field::zmod::factorize;
+/// assert_eq!(factorize(360), [(2, 3), (3, 2), (5, 1)]);
+/// ```
+pub fn factorize(mut n: u64) -> Vec<(u64, u32)> {
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut primes = Vec::new();
+    for p in 2..100 {
+        while n.is_multiple_of(p) {
+            primes.push(p);
+            n /= p;
+        }
+    }
+    let mut stack = vec![n];
+    while let Some(m) = stack.pop() {
+        if m == 1 {
+            continue;
+        }
+        if is_prime(m) {
+            primes.push(m);
+            continue;
+        }
+        let d = pollard_rho(m);
+        stack.push(d);
+        stack.push(m / d);
+    }
+    primes.sort_unstable();
+    let mut result: Vec<(u64, u32)> = Vec::new();
+    for p in primes {
+        match result.last_mut() {
+            Some((q, e)) if *q == p => *e += 1,
+            _ => result.push((p, 1)),
+        }
+    }
+    result
+}
+/// 合成数 `n` の非自明な約数を1つ返す
+fn pollard_rho(n: u64) -> u64 {
+    for c in 1.. {
+        let f = |x| add_mod(mul_mod(x, x, n), c, n);
+        let (mut x, mut y, mut d) = (2, 2, 1);
+        while d == 1 {
+            x = f(x);
+            y = f(f(y));
+            d = gcd(x.abs_diff(y), n);
+        }
+        if d != n {
+            return d;
+        }
+    }
+    unreachable!()
+}
+/// オイラーのφ関数
+/// # Panics
+/// `n == 0` のとき
+pub fn euler_phi(n: u64) -> u64 {
+    if n == 0 {
+        panic!("法が0");
+    }
+    let mut phi = n;
+    for (p, _) in factorize(n) {
+        phi = phi / p * (p - 1);
+    }
+    phi
+}
+/// カーマイケルのλ関数. (Z/nZ)^× の元の位数の最小公倍数.
+/// # Panics
+/// `n == 0` のとき
+pub fn carmichael_lambda(n: u64) -> u64 {
+    if n == 0 {
+        panic!("法が0");
+    }
+    let mut lambda = 1;
+    for (p, e) in factorize(n) {
+        let l = if p == 2 && e >= 3 {
+            1 << (e - 2)
+        } else {
+            p.pow(e - 1) * (p - 1)
+        };
+        lambda = lambda / gcd(lambda, l) * l;
+    }
+    lambda
+}
+impl From<Field> for Zmod {
+    fn from(x: Field) -> Zmod {
+        Zmod {
+            v: x.value(),
+            n: x.modulus(),
+        }
+    }
+}
+impl TryFrom<Zmod> for Field {
+    type Error = FieldError;
+    fn try_from(x: Zmod) -> Result<Field, FieldError> {
+        if !is_prime(x.n) {
+            return Err(FieldError::NotPrime(x.n));
+        }
+        Ok(Field::from_reduced(x.v, x.n))
+    }
+}
+impl Ring for Zmod {
+    fn zero_like(&self) -> Self {
+        Self { v: 0, n: self.n }
+    }
+    fn one_like(&self) -> Self {
+        Self::new(1, self.n)
+    }
+    fn is_zero(&self) -> bool {
+        self.v == 0
+    }
+    fn mul_usize(&self, k: usize) -> Self {
+        Self {
+            v: mul_mod(self.v, k as u64 % self.n, self.n),
+            n: self.n,
+        }
+    }
+}
+impl fmt::Display for Zmod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.v)
+    }
+}
+impl ops::Neg for Zmod {
+    type Output = Zmod;
+    fn neg(self) -> Self {
+        Self {
+            v: sub_mod(0, self.v, self.n),
+            n: self.n,
+        }
+    }
+}
+impl ops::Add for Zmod {
+    type Output = Zmod;
+    fn add(self, other: Zmod) -> Self {
+        debug_assert_eq!(self.n, other.n, "法が一致しない");
+        Self {
+            v: add_mod(self.v, other.v, self.n),
+            n: self.n,
+        }
+    }
+}
+impl ops::Add<i64> for Zmod {
+    type Output = Zmod;
+    fn add(self, other: i64) -> Self {
+        Self {
+            v: add_mod(self.v, reduce(other, self.n), self.n),
+            n: self.n,
+        }
+    }
+}
+impl ops::AddAssign for Zmod {
+    fn add_assign(&mut self, other: Zmod) {
+        debug_assert_eq!(self.n, other.n, "法が一致しない");
+        self.v = add_mod(self.v, other.v, self.n);
+    }
+}
+impl ops::Sub for Zmod {
+    type Output = Zmod;
+    fn sub(self, other: Zmod) -> Self {
+        debug_assert_eq!(self.n, other.n, "法が一致しない");
+        Self {
+            v: sub_mod(self.v, other.v, self.n),
+            n: self.n,
+        }
+    }
+}
+impl ops::SubAssign for Zmod {
+    fn sub_assign(&mut self, other: Zmod) {
+        debug_assert_eq!(self.n, other.n, "法が一致しない");
+        self.v = sub_mod(self.v, other.v, self.n);
+    }
+}
+impl ops::Mul for Zmod {
+    type Output = Zmod;
+    fn mul(self, other: Zmod) -> Self {
+        debug_assert_eq!(self.n, other.n, "法が一致しない");
+        Self {
+            v: mul_mod(self.v, other.v, self.n),
+            n: self.n,
+        }
+    }
+}
+impl ops::Mul<i64> for Zmod {
+    type Output = Zmod;
+    fn mul(self, other: i64) -> Self {
+        Self {
+            v: mul_mod(self.v, reduce(other, self.n), self.n),
+            n: self.n,
+        }
+    }
+}
+impl ops::MulAssign for Zmod {
+    fn mul_assign(&mut self, other: Zmod) {
+        debug_assert_eq!(self.n, other.n, "法が一致しない");
+        self.v = mul_mod(self.v, other.v, self.n);
+    }
+}
+impl ops::MulAssign<i64> for Zmod {
+    fn mul_assign(&mut self, other: i64) {
+        self.v = mul_mod(self.v, reduce(other, self.n), self.n);
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manipulative::Manipulative;
+
+    #[test]
+    fn arith_test() {
+        assert_eq!(Zmod::new(5, 12) + Zmod::new(9, 12), Zmod::new(2, 12));
+        assert_eq!(Zmod::new(5, 12) - Zmod::new(9, 12), Zmod::new(8, 12));
+        assert_eq!(Zmod::new(4, 12) * Zmod::new(9, 12), Zmod::new(0, 12));
+        assert_eq!(-Zmod::new(5, 12), Zmod::new(7, 12));
+        assert_eq!(Zmod::new(-1, 12).v, 11);
+        assert_eq!(Zmod::new(3, 12) * -2, Zmod::new(6, 12));
+        assert_eq!(Zmod::new(2, 9).pow(6), Zmod::new(1, 9));
+    }
+    #[test]
+    fn unit_test() {
+        let units: Vec<u64> = (0..12)
+            .map(|v| Zmod::new(v, 12))
+            .filter(|x| x.is_unit())
+            .map(|x| x.v)
+            .collect();
+        assert_eq!(units, [1, 5, 7, 11]);
+        for &v in &units {
+            let x = Zmod::new(v as i64, 12);
+            assert_eq!(x * x.checked_inv().unwrap(), Zmod::new(1, 12));
+        }
+        assert_eq!(
+            Zmod::new(6, 12).checked_inv(),
+            Err(FieldError::NotInvertible)
+        );
+        assert_eq!(
+            Zmod::new(1, 12).checked_div(Zmod::new(5, 12)),
+            Ok(Zmod::new(5, 12))
+        );
+        assert_eq!(
+            Zmod::new(1, 12).checked_div(Zmod::new(1, 13)),
+            Err(FieldError::ModulusMismatch(12, 13))
+        );
+    }
+    #[test]
+    fn phi_lambda_test() {
+        let phi: Vec<u64> = (1..=12).map(euler_phi).collect();
+        assert_eq!(phi, [1, 1, 2, 2, 4, 2, 6, 4, 6, 4, 10, 4]);
+        let lambda: Vec<u64> = (1..=16).map(carmichael_lambda).collect();
+        assert_eq!(lambda, [1, 1, 2, 2, 4, 2, 6, 2, 6, 4, 10, 2, 12, 6, 4, 4]);
+        assert_eq!(carmichael_lambda(561), 80);
+        let p = (1u64 << 61) - 1;
+        assert_eq!(euler_phi(p), p - 1);
+        assert_eq!(
+            factorize(4_611_686_014_132_420_609), // (2^31-1)^2
+            [(2_147_483_647, 2)]
+        );
+        assert_eq!(factorize(1), []);
+        assert_eq!(factorize(0), []);
+    }
+    #[test]
+    #[should_panic(expected = "法が0")]
+    fn phi_zero_test() {
+        euler_phi(0);
+    }
+    #[test]
+    #[should_panic(expected = "法が0")]
+    fn lambda_zero_test() {
+        carmichael_lambda(0);
+    }
+    #[test]
+    fn order_test() {
+        assert_eq!(Zmod::new(2, 11).order(), Some(10));
+        assert_eq!(Zmod::new(3, 11).order(), Some(5));
+        assert_eq!(Zmod::new(1, 8).order(), Some(1));
+        assert_eq!(Zmod::new(3, 8).order(), Some(2));
+        assert_eq!(Zmod::new(2, 8).order(), None);
+        assert_eq!(Zmod::new(0, 1).order(), Some(1));
+        for n in 2..60 {
+            for v in 0..n as i64 {
+                let x = Zmod::new(v, n);
+                let naive = (1..=n).find(|&k| x.pow(k).v == 1);
+                assert_eq!(x.order(), naive.filter(|_| x.is_unit()));
+            }
+        }
+    }
+    #[test]
+    fn conversion_test() {
+        assert_eq!(Zmod::from(Field::new(3, 7)), Zmod::new(3, 7));
+        assert_eq!(Field::try_from(Zmod::new(3, 7)), Ok(Field::new(3, 7)));
+        assert_eq!(
+            Field::try_from(Zmod::new(3, 8)),
+            Err(FieldError::NotPrime(8))
+        );
+    }
+    #[test]
+    fn manipulative_test() {
+        let f = |v: &[i64]| Manipulative::new(v.iter().map(|v| Zmod::new(*v, 8)).collect());
+        // (2x + 2)^2 = 4x^2 + 8x + 4 = 4x^2 + 4 (mod 8)
+        assert_eq!(&f(&[2, 2]) * &f(&[2, 2]), f(&[4, 0, 4]));
+        assert_eq!(f(&[1, 3, 5]).assign(Zmod::new(2, 8)), Zmod::new(27, 8));
+    }
+}